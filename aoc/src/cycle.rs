@@ -0,0 +1,159 @@
+use std::{collections::HashMap, hash::Hash};
+
+/// Shape of the sequence `initial, step(initial), step(step(initial)), ...` once it loops.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cycle {
+    /// Index of the first state that is part of the cycle.
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// Smallest index reaching the same state as index `n`.
+    pub fn equivalent_step(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+}
+
+/// Hash based detection, remembering every state until the first repetition.
+pub fn find_cycle<T, F>(initial: T, step: F) -> Cycle
+where
+    T: Hash + Eq + Clone,
+    F: FnMut(&T) -> T,
+{
+    find_cycle_with_history(initial, step).0
+}
+
+/// Same as [`find_cycle`], also returning every state from the initial one to the end of the first cycle.
+pub fn find_cycle_with_history<T, F>(initial: T, mut step: F) -> (Cycle, Vec<T>)
+where
+    T: Hash + Eq + Clone,
+    F: FnMut(&T) -> T,
+{
+    let mut seen = HashMap::new();
+    let mut history = Vec::new();
+    let mut state = initial;
+    loop {
+        if let Some(&start) = seen.get(&state) {
+            let length = history.len() - start;
+            return (Cycle { start, length }, history);
+        }
+        seen.insert(state.clone(), history.len());
+        let next = step(&state);
+        history.push(state);
+        state = next;
+    }
+}
+
+/// State reached after `n` steps, jumping over the repetitions once a cycle is found.
+pub fn state_at<T, F>(initial: T, step: F, n: usize) -> T
+where
+    T: Hash + Eq + Clone,
+    F: FnMut(&T) -> T,
+{
+    let (cycle, mut history) = find_cycle_with_history(initial, step);
+    history.swap_remove(cycle.equivalent_step(n))
+}
+
+/// Brent's algorithm, only keeping a couple of states alive at a time.
+pub fn find_cycle_brent<T, F>(initial: T, mut step: F) -> Cycle
+where
+    T: Eq + Clone,
+    F: FnMut(&T) -> T,
+{
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    let mut tortoise = initial.clone();
+    let mut hare = initial;
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    Cycle { start, length }
+}
+
+/// Floyd's tortoise and hare algorithm, only keeping a couple of states alive at a time.
+pub fn find_cycle_floyd<T, F>(initial: T, mut step: F) -> Cycle
+where
+    T: Eq + Clone,
+    F: FnMut(&T) -> T,
+{
+    let mut tortoise = step(&initial);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        hare = step(&hare);
+    }
+
+    let mut start = 0;
+    tortoise = initial;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    let mut length = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        length += 1;
+    }
+
+    Cycle { start, length }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0, 1, 2, 3, then 4..=10 repeating
+    fn step(n: &u32) -> u32 {
+        if *n == 10 {
+            4
+        } else {
+            n + 1
+        }
+    }
+
+    #[test]
+    fn cycles() {
+        let expected = Cycle {
+            start: 4,
+            length: 7,
+        };
+        assert_eq!(find_cycle(0, step), expected);
+        assert_eq!(find_cycle_brent(0, step), expected);
+        assert_eq!(find_cycle_floyd(0, step), expected);
+
+        assert_eq!(state_at(0, step, 2), 2);
+        assert_eq!(state_at(0, step, 11), 4);
+        assert_eq!(
+            state_at(0, step, 1_000_000_000),
+            4 + (1_000_000_000 - 4) % 7
+        );
+    }
+}
@@ -1,4 +1,9 @@
-use std::{mem::MaybeUninit, str::pattern::Pattern};
+use std::{
+    fmt,
+    mem::MaybeUninit,
+    num::ParseIntError,
+    str::{pattern::Pattern, FromStr},
+};
 
 pub trait VecExtensions {
     type Item;
@@ -41,10 +46,82 @@ where
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IntegersError {
+    Count { expected: usize, found: usize },
+    Parse(ParseIntError),
+}
+
+impl fmt::Display for IntegersError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntegersError::Count { expected, found } => {
+                write!(f, "expected {expected} integers, found {found}")
+            }
+            IntegersError::Parse(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for IntegersError {}
+
 pub trait StrExtensions {
     fn match_advance(&self, pattern: &Self) -> Option<&Self>;
     fn consume_until<'a, P: Pattern>(&'a self, pattern: P) -> (&'a Self, &'a Self);
     fn split_exact<'a, const N: usize, P: Pattern>(&'a self, pattern: P) -> Option<[&'a Self; N]>;
+    /// Every integer in the string, a `-` right before digits making it negative unless it follows
+    /// a digit, so ranges such as `1-3` give `1` and `3`.
+    fn signed_integers<T>(&self) -> impl Iterator<Item = Result<T, ParseIntError>>
+    where
+        T: FromStr<Err = ParseIntError>;
+    /// Every run of digits in the string, so `3-5` gives `3` and `5`.
+    fn unsigned_integers<T>(&self) -> impl Iterator<Item = Result<T, ParseIntError>>
+    where
+        T: FromStr<Err = ParseIntError>;
+    fn signed_integers_exact<const N: usize, T>(&self) -> Result<[T; N], IntegersError>
+    where
+        T: FromStr<Err = ParseIntError>;
+    fn unsigned_integers_exact<const N: usize, T>(&self) -> Result<[T; N], IntegersError>
+    where
+        T: FromStr<Err = ParseIntError>;
+}
+
+fn integers<T>(s: &str, signed: bool) -> impl Iterator<Item = Result<T, ParseIntError>> + '_
+where
+    T: FromStr<Err = ParseIntError>,
+{
+    let bytes = s.as_bytes();
+    let mut i = 0;
+    std::iter::from_fn(move || {
+        let start = i + bytes[i..].iter().position(u8::is_ascii_digit)?;
+        let end = start
+            + bytes[start..]
+                .iter()
+                .position(|b| !b.is_ascii_digit())
+                .unwrap_or(bytes.len() - start);
+        i = end;
+        let negative = signed
+            && start > 0
+            && bytes[start - 1] == b'-'
+            && !(start > 1 && bytes[start - 2].is_ascii_digit());
+        let start = if negative { start - 1 } else { start };
+        Some(s[start..end].parse())
+    })
+}
+
+fn integers_exact<const N: usize, T>(s: &str, signed: bool) -> Result<[T; N], IntegersError>
+where
+    T: FromStr<Err = ParseIntError>,
+{
+    let values = integers(s, signed)
+        .collect::<Result<Vec<T>, _>>()
+        .map_err(IntegersError::Parse)?;
+    values
+        .try_into()
+        .map_err(|values: Vec<T>| IntegersError::Count {
+            expected: N,
+            found: values.len(),
+        })
 }
 
 impl StrExtensions for str {
@@ -88,4 +165,70 @@ impl StrExtensions for str {
             Some(arr)
         }
     }
+
+    fn signed_integers<T>(&self) -> impl Iterator<Item = Result<T, ParseIntError>>
+    where
+        T: FromStr<Err = ParseIntError>,
+    {
+        integers(self, true)
+    }
+
+    fn unsigned_integers<T>(&self) -> impl Iterator<Item = Result<T, ParseIntError>>
+    where
+        T: FromStr<Err = ParseIntError>,
+    {
+        integers(self, false)
+    }
+
+    fn signed_integers_exact<const N: usize, T>(&self) -> Result<[T; N], IntegersError>
+    where
+        T: FromStr<Err = ParseIntError>,
+    {
+        integers_exact(self, true)
+    }
+
+    fn unsigned_integers_exact<const N: usize, T>(&self) -> Result<[T; N], IntegersError>
+    where
+        T: FromStr<Err = ParseIntError>,
+    {
+        integers_exact(self, false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integer_extraction() {
+        let line = "Sensor at x=-2, y=15: beacon 3-5";
+        let signed: Vec<i32> = line.signed_integers().collect::<Result<_, _>>().unwrap();
+        assert_eq!(signed, [-2, 15, 3, 5]);
+        let signed: Vec<i32> = "-1--3 a"
+            .signed_integers()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(signed, [-1, -3]);
+        let unsigned: Vec<u8> = line.unsigned_integers().collect::<Result<_, _>>().unwrap();
+        assert_eq!(unsigned, [2, 15, 3, 5]);
+        assert!("x=-1".unsigned_integers::<i8>().all(|v| v == Ok(1)));
+        assert_eq!("no numbers".signed_integers::<i64>().count(), 0);
+
+        assert_eq!(
+            "#1 @ 1,3: 4x4".unsigned_integers_exact(),
+            Ok([1u32, 1, 3, 4, 4])
+        );
+        assert_eq!(
+            "1 2 3".signed_integers_exact::<2, i32>(),
+            Err(IntegersError::Count {
+                expected: 2,
+                found: 3
+            })
+        );
+        assert!(matches!(
+            "300 -1".unsigned_integers_exact::<2, u8>(),
+            Err(IntegersError::Parse(_))
+        ));
+        assert!("-129".signed_integers::<i8>().next().unwrap().is_err());
+    }
 }
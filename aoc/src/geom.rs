@@ -1,15 +1,63 @@
+use std::ops::{Neg, Sub};
+
+pub mod hex;
+pub mod threed;
+
+fn abs_diff<T: Sub<T, Output = T> + Ord>(a: T, b: T) -> T {
+    if a > b {
+        a - b
+    } else {
+        b - a
+    }
+}
+
+fn abs<T: Neg<Output = T> + Ord + From<i8>>(v: T) -> T {
+    if v < T::from(0) {
+        -v
+    } else {
+        v
+    }
+}
+
 pub mod twod {
+    mod automaton;
+    pub use automaton::*;
+    mod compression;
+    pub use compression::*;
+    mod image;
+    pub use image::*;
+    mod markers;
+    pub use markers::*;
+    mod notation;
+    pub use notation::*;
+    mod polygon;
+    pub use polygon::*;
+    mod rect;
+    pub use rect::*;
+    mod render;
+    pub use render::*;
+    mod segment;
+    pub use segment::*;
+    mod tiling;
+    pub use tiling::*;
+    mod tokens;
+    pub use tokens::*;
+    mod view;
+    pub use view::*;
+
+    use super::{abs, abs_diff};
     use crate::extensions::SliceExtensions;
     use core::fmt::{self, Debug};
     use std::{
         fmt::Display,
         iter,
-        ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+        ops::{Add, AddAssign, Div, Mul, Neg, Rem, Sub, SubAssign},
         str::FromStr,
         vec,
     };
 
     #[derive(PartialEq, Eq, Hash, Clone, Copy, Default, Debug)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct Pos<T>(pub T, pub T);
 
     impl<T, U> From<(U, U)> for Pos<T>
@@ -22,6 +70,7 @@ pub mod twod {
     }
 
     #[derive(PartialEq, Eq, Hash, Clone, Copy, Default, Debug)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct Vec<T>(pub T, pub T);
 
     impl<T> Vec<T>
@@ -163,7 +212,123 @@ pub mod twod {
         }
     }
 
+    impl<T> Pos<T>
+    where
+        T: Copy + Ord + Add<T, Output = T> + Sub<T, Output = T>,
+    {
+        pub fn manhattan(&self, other: &Pos<T>) -> T {
+            abs_diff(self.0, other.0) + abs_diff(self.1, other.1)
+        }
+
+        pub fn chebyshev(&self, other: &Pos<T>) -> T {
+            abs_diff(self.0, other.0).max(abs_diff(self.1, other.1))
+        }
+
+        pub fn distance_squared(&self, other: &Pos<T>) -> T
+        where
+            T: Mul<T, Output = T>,
+        {
+            let (dx, dy) = (abs_diff(self.0, other.0), abs_diff(self.1, other.1));
+            dx * dx + dy * dy
+        }
+    }
+
+    impl<T> Pos<T> {
+        /// Converts each coordinate, failing if one doesn't fit in `U`.
+        pub fn try_cast<U: TryFrom<T>>(self) -> Option<Pos<U>> {
+            Some(Pos(U::try_from(self.0).ok()?, U::try_from(self.1).ok()?))
+        }
+    }
+
+    impl Pos<usize> {
+        /// Moves by `v`, failing instead of going below 0 or overflowing.
+        pub fn checked_add(self, v: Vec<isize>) -> Option<Pos<usize>> {
+            Some(Pos(
+                self.0.checked_add_signed(v.0)?,
+                self.1.checked_add_signed(v.1)?,
+            ))
+        }
+
+        pub fn checked_add_dir(self, dir: Dir) -> Option<Pos<usize>> {
+            self.checked_add(dir.as_vec())
+        }
+    }
+
+    impl<T> Vec<T> {
+        /// Converts each coordinate, failing if one doesn't fit in `U`.
+        pub fn try_cast<U: TryFrom<T>>(self) -> Option<Vec<U>> {
+            Some(Vec(U::try_from(self.0).ok()?, U::try_from(self.1).ok()?))
+        }
+    }
+
+    impl<T> Vec<T>
+    where
+        T: Copy + Add<T, Output = T> + Sub<T, Output = T> + Mul<T, Output = T>,
+    {
+        pub fn dot(&self, other: &Vec<T>) -> T {
+            self.0 * other.0 + self.1 * other.1
+        }
+
+        /// Z component of the 3D cross product, positive when `other` is clockwise from `self`
+        /// (the y axis pointing down).
+        pub fn cross(&self, other: &Vec<T>) -> T {
+            self.0 * other.1 - self.1 * other.0
+        }
+
+        pub fn len_squared(&self) -> T {
+            self.dot(self)
+        }
+    }
+
+    impl<T> Vec<T>
+    where
+        T: Copy + Ord + Neg<Output = T> + Add<T, Output = T> + From<i8>,
+    {
+        pub fn abs(&self) -> Self {
+            Vec(abs(self.0), abs(self.1))
+        }
+
+        pub fn manhattan_len(&self) -> T {
+            abs(self.0) + abs(self.1)
+        }
+
+        pub fn chebyshev_len(&self) -> T {
+            abs(self.0).max(abs(self.1))
+        }
+
+        /// Each coordinate replaced by -1, 0 or 1, i.e. a single step going towards `self`.
+        pub fn signum(&self) -> Self {
+            let signum = |v: T| T::from((v > T::from(0)) as i8 - (v < T::from(0)) as i8);
+            Vec(signum(self.0), signum(self.1))
+        }
+
+        /// Direction of the step going towards `self`, `None` for the null vector.
+        pub fn step_dir(&self) -> Option<Dir> {
+            let Vec(x, y) = self.signum();
+            all_dirs().find(|dir| dir.as_vec::<T>() == Vec(x, y))
+        }
+    }
+
+    impl<T> Vec<T>
+    where
+        T: Copy + Ord + Neg<Output = T> + Div<T, Output = T> + Rem<T, Output = T> + From<i8>,
+    {
+        /// Smallest vector with the same direction and integer coordinates.
+        pub fn reduced(&self) -> Self {
+            let (mut a, mut b) = (abs(self.0), abs(self.1));
+            while b != T::from(0) {
+                (a, b) = (b, a % b);
+            }
+            if a == T::from(0) {
+                *self
+            } else {
+                Vec(self.0 / a, self.1 / a)
+            }
+        }
+    }
+
     #[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum Dir {
         Up,
         Right,
@@ -294,13 +459,48 @@ pub mod twod {
         }
     }
 
-    #[derive(Clone, PartialEq, Eq, Hash)]
+    #[derive(Clone, PartialEq, Eq, Hash, Debug)]
+    #[cfg_attr(
+        feature = "serde",
+        derive(serde::Serialize, serde::Deserialize),
+        serde(try_from = "RawMap<Cell>")
+    )]
     pub struct Map<Cell> {
         pub width: usize,
         pub height: usize,
         pub cells: std::vec::Vec<Cell>,
     }
 
+    /// Deserialized fields of a [`Map`], before checking that the cells fill it.
+    #[cfg(feature = "serde")]
+    #[derive(serde::Deserialize)]
+    struct RawMap<Cell> {
+        width: usize,
+        height: usize,
+        cells: std::vec::Vec<Cell>,
+    }
+
+    #[cfg(feature = "serde")]
+    impl<Cell> TryFrom<RawMap<Cell>> for Map<Cell> {
+        type Error = String;
+
+        fn try_from(raw: RawMap<Cell>) -> Result<Self, Self::Error> {
+            if raw.width.checked_mul(raw.height) != Some(raw.cells.len()) {
+                return Err(format!(
+                    "{} cells can't fill a {}x{} map",
+                    raw.cells.len(),
+                    raw.width,
+                    raw.height
+                ));
+            }
+            Ok(Map {
+                width: raw.width,
+                height: raw.height,
+                cells: raw.cells,
+            })
+        }
+    }
+
     impl<Cell> fmt::Display for Map<Cell>
     where
         Cell: Display,
@@ -384,6 +584,78 @@ pub mod twod {
                 .enumerate()
                 .map(|(i, c)| (Self::pos_from_offset_width(i, self.width), c))
         }
+
+        /// Row `y`, which is empty when the map has no columns.
+        pub fn row(&self, y: usize) -> Option<&[Cell]> {
+            let width = self.width;
+            (y < self.height).then(|| &self.cells[y * width..(y + 1) * width])
+        }
+
+        pub fn row_mut(&mut self, y: usize) -> Option<&mut [Cell]> {
+            let width = self.width;
+            (y < self.height).then(|| &mut self.cells[y * width..(y + 1) * width])
+        }
+
+        pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[Cell]> + ExactSizeIterator {
+            (0..self.height).map(|y| self.row(y).unwrap())
+        }
+
+        pub fn rows_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut [Cell]> {
+            // Chunks can't have a length of 0, so the rows of a map without columns are added apart.
+            let empty_rows = if self.width == 0 { self.height } else { 0 };
+            self.cells
+                .chunks_mut(self.width.max(1))
+                .chain((0..empty_rows).map(|_| Default::default()))
+        }
+
+        pub fn column(&self, x: usize) -> Option<impl DoubleEndedIterator<Item = &Cell>> {
+            if x < self.width {
+                Some(self.cells.iter().skip(x).step_by(self.width))
+            } else {
+                None
+            }
+        }
+
+        pub fn column_mut(
+            &mut self,
+            x: usize,
+        ) -> Option<impl DoubleEndedIterator<Item = &mut Cell>> {
+            if x < self.width {
+                Some(self.cells.iter_mut().skip(x).step_by(self.width))
+            } else {
+                None
+            }
+        }
+
+        pub fn columns(
+            &self,
+        ) -> impl DoubleEndedIterator<Item = impl DoubleEndedIterator<Item = &Cell>> {
+            (0..self.width).map(|x| self.column(x).unwrap())
+        }
+
+        pub fn columns_mut(&mut self) -> impl DoubleEndedIterator<Item = vec::IntoIter<&mut Cell>> {
+            // The cells of a column are interleaved with the others, so they can only be borrowed
+            // separately by collecting the references first.
+            let mut columns: vec::Vec<vec::Vec<&mut Cell>> =
+                iter::repeat_with(vec::Vec::new).take(self.width).collect();
+            for (i, cell) in self.cells.iter_mut().enumerate() {
+                columns[i % self.width].push(cell);
+            }
+            columns.into_iter().map(|column| column.into_iter())
+        }
+
+        /// Position next to `p` in the given direction, if it is inside the map.
+        pub fn neighbour(&self, p: Pos<usize>, dir: Dir) -> Option<Pos<usize>> {
+            let p = p.checked_add_dir(dir)?;
+            self.offset_from_pos(p).map(|_| p)
+        }
+
+        /// Cells seen from `from` looking in the given direction, up to the edge of the map.
+        /// The starting cell is not included.
+        pub fn ray(&self, from: Pos<usize>, dir: Dir) -> impl Iterator<Item = (Pos<usize>, &Cell)> {
+            iter::successors(self.neighbour(from, dir), move |&p| self.neighbour(p, dir))
+                .map(|p| (p, self.get(p).unwrap()))
+        }
     }
 
     impl<Cell> Map<Cell>
@@ -422,74 +694,173 @@ pub mod twod {
             sent
         }
 
-        pub fn split_regions(&self, width: usize, height: usize) -> Option<vec::Vec<Self>> {
-            if self.width % width != 0 || self.height % height != 0 {
-                None
-            } else {
-                let mut result = vec::Vec::with_capacity(self.width / width * self.height / height);
-                let mut x = 0;
-                let mut y = 0;
-                while x < self.width && y < self.height {
-                    let mut sub = vec::Vec::with_capacity(width * height);
-                    for i in 0..height {
-                        let start = self.offset_from_pos(Pos(x, y + i)).unwrap();
-                        sub.extend_from_slice(&self.cells[start..start + width]);
-                    }
-
-                    result.push(Self {
-                        width,
-                        height,
-                        cells: sub,
-                    });
-
-                    x += width;
-                    if x >= self.width {
-                        y += width;
-                        x = 0;
-                    }
-                }
-                Some(result)
+        pub fn rotate_left(&self) -> Self {
+            self.oriented(Orientation::RotateLeft).to_map()
+        }
+
+        pub fn rotate_180(&self) -> Self {
+            self.oriented(Orientation::Rotate180).to_map()
+        }
+
+        pub fn vertical_flip(&self) -> Self {
+            self.oriented(Orientation::VerticalFlip).to_map()
+        }
+
+        pub fn transpose(&self) -> Self {
+            self.oriented(Orientation::Transpose).to_map()
+        }
+
+        pub fn anti_transpose(&self) -> Self {
+            self.oriented(Orientation::AntiTranspose).to_map()
+        }
+    }
+
+    /// One of the 8 symmetries of a rectangle.
+    #[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub enum Orientation {
+        Identity,
+        RotateRight,
+        Rotate180,
+        RotateLeft,
+        HorizontalFlip,
+        VerticalFlip,
+        Transpose,
+        AntiTranspose,
+    }
+
+    pub const ALL_ORIENTATIONS: [Orientation; 8] = [
+        Orientation::Identity,
+        Orientation::RotateRight,
+        Orientation::Rotate180,
+        Orientation::RotateLeft,
+        Orientation::HorizontalFlip,
+        Orientation::VerticalFlip,
+        Orientation::Transpose,
+        Orientation::AntiTranspose,
+    ];
+
+    impl Orientation {
+        /// Whether width and height are exchanged by this orientation.
+        pub fn swaps_axes(&self) -> bool {
+            matches!(
+                self,
+                Orientation::RotateRight
+                    | Orientation::RotateLeft
+                    | Orientation::Transpose
+                    | Orientation::AntiTranspose
+            )
+        }
+
+        /// Maps a position in the oriented view back to the position in a source of size `width` x `height`.
+        pub fn source_pos(&self, Pos(x, y): Pos<usize>, width: usize, height: usize) -> Pos<usize> {
+            match self {
+                Orientation::Identity => Pos(x, y),
+                Orientation::RotateRight => Pos(y, height - 1 - x),
+                Orientation::Rotate180 => Pos(width - 1 - x, height - 1 - y),
+                Orientation::RotateLeft => Pos(width - 1 - y, x),
+                Orientation::HorizontalFlip => Pos(width - 1 - x, y),
+                Orientation::VerticalFlip => Pos(x, height - 1 - y),
+                Orientation::Transpose => Pos(y, x),
+                Orientation::AntiTranspose => Pos(width - 1 - y, height - 1 - x),
             }
         }
     }
 
-    pub trait MapCombinable {
-        type Output;
+    /// Read-only view of a map under an [`Orientation`], remapping coordinates instead of copying cells.
+    pub struct OrientedMap<'a, Cell> {
+        map: &'a Map<Cell>,
+        orientation: Orientation,
+    }
 
-        fn combine(&self, maps_per_row: usize) -> Self::Output;
+    impl<'a, Cell> Clone for OrientedMap<'a, Cell> {
+        fn clone(&self) -> Self {
+            *self
+        }
     }
 
-    impl<Cell> MapCombinable for [Map<Cell>]
-    where
-        Cell: Clone + Default,
-    {
-        type Output = Option<Map<Cell>>;
+    impl<'a, Cell> Copy for OrientedMap<'a, Cell> {}
+
+    impl<'a, Cell> OrientedMap<'a, Cell> {
+        pub fn orientation(&self) -> Orientation {
+            self.orientation
+        }
 
-        fn combine(&self, maps_per_row: usize) -> Self::Output {
-            if self.is_empty() {
+        pub fn width(&self) -> usize {
+            if self.orientation.swaps_axes() {
+                self.map.height
+            } else {
+                self.map.width
+            }
+        }
+
+        pub fn height(&self) -> usize {
+            if self.orientation.swaps_axes() {
+                self.map.width
+            } else {
+                self.map.height
+            }
+        }
+
+        pub fn get(&self, p: Pos<usize>) -> Option<&'a Cell> {
+            if p.0 >= self.width() || p.1 >= self.height() {
                 None
             } else {
-                let sub_width = self[0].width;
-                let sub_height = self[0].height;
-                let mut result = Map::new(
-                    sub_width * maps_per_row,
-                    sub_height * (self.len() / maps_per_row),
-                    Cell::default(),
-                );
-
-                for (i, sub) in self.iter().enumerate() {
-                    if sub.width != sub_width || sub.height != sub_height {
-                        return None;
-                    }
-                    let top_left = Pos(i % maps_per_row * sub_width, i / maps_per_row * sub_height);
-                    for (Pos(x, y), cell) in sub.enumerate_cells() {
-                        *result.get_mut(top_left + Vec(x, y)).unwrap() = cell.clone();
-                    }
+                self.map.get(
+                    self.orientation
+                        .source_pos(p, self.map.width, self.map.height),
+                )
+            }
+        }
+
+        pub fn enumerate_cells(&self) -> impl Iterator<Item = (Pos<usize>, &'a Cell)> + '_ {
+            let width = self.width();
+            (0..width * self.height()).map(move |i| {
+                let p = Pos(i % width, i / width);
+                (p, self.get(p).unwrap())
+            })
+        }
+    }
+
+    impl<'a, Cell> OrientedMap<'a, Cell>
+    where
+        Cell: Clone,
+    {
+        pub fn to_map(&self) -> Map<Cell> {
+            Map {
+                width: self.width(),
+                height: self.height(),
+                cells: self.enumerate_cells().map(|(_, c)| c.clone()).collect(),
+            }
+        }
+    }
+
+    impl<'a, Cell> fmt::Display for OrientedMap<'a, Cell>
+    where
+        Cell: Display,
+    {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            for (Pos(x, y), cell) in self.enumerate_cells() {
+                if x == 0 && y > 0 {
+                    writeln!(f)?;
                 }
+                write!(f, "{cell}")?;
+            }
+            Ok(())
+        }
+    }
 
-                Some(result)
+    impl<Cell> Map<Cell> {
+        pub fn oriented(&self, orientation: Orientation) -> OrientedMap<'_, Cell> {
+            OrientedMap {
+                map: self,
+                orientation,
             }
         }
+
+        pub fn orientations(&self) -> impl Iterator<Item = OrientedMap<'_, Cell>> {
+            ALL_ORIENTATIONS.into_iter().map(|o| self.oriented(o))
+        }
     }
 
     impl<Cell> Map<Cell>
@@ -503,21 +874,82 @@ pub mod twod {
         }
     }
 
-    pub struct MapBuilder<Cell>
+    /// Why a map could not be parsed, lines and columns starting at 1.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum MapParseError<E> {
+        InvalidCell {
+            line: usize,
+            column: usize,
+            c: char,
+            error: E,
+        },
+        /// Column is the index of the token in the line.
+        InvalidToken {
+            line: usize,
+            column: usize,
+            token: String,
+            error: E,
+        },
+        RaggedRow {
+            line: usize,
+            expected: usize,
+            found: usize,
+        },
+    }
+
+    impl<E: Display> Display for MapParseError<E> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                MapParseError::InvalidCell {
+                    line,
+                    column,
+                    c,
+                    error,
+                } => write!(
+                    f,
+                    "invalid cell {c:?} at line {line}, column {column}: {error}"
+                ),
+                MapParseError::InvalidToken {
+                    line,
+                    column,
+                    token,
+                    error,
+                } => write!(
+                    f,
+                    "invalid token {token:?} at line {line}, column {column}: {error}"
+                ),
+                MapParseError::RaggedRow {
+                    line,
+                    expected,
+                    found,
+                } => write!(f, "line {line} has {found} cells instead of {expected}"),
+            }
+        }
+    }
+
+    impl<E> std::error::Error for MapParseError<E>
     where
-        Cell: TryFrom<char>,
+        E: std::error::Error + 'static,
     {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            match self {
+                MapParseError::InvalidCell { error, .. }
+                | MapParseError::InvalidToken { error, .. } => Some(error),
+                MapParseError::RaggedRow { .. } => None,
+            }
+        }
+    }
+
+    /// Row bookkeeping shared by the map builders.
+    struct MapRows<Cell, E> {
         width: usize,
         height: usize,
         cells: std::vec::Vec<Cell>,
-        error: Option<<Cell as TryFrom<char>>::Error>,
+        error: Option<MapParseError<E>>,
     }
 
-    impl<Cell> MapBuilder<Cell>
-    where
-        Cell: TryFrom<char>,
-    {
-        pub fn new() -> Self {
+    impl<Cell, E> MapRows<Cell, E> {
+        fn new() -> Self {
             Self {
                 width: 0,
                 height: 0,
@@ -526,44 +958,88 @@ pub mod twod {
             }
         }
 
-        pub fn feed_line(&mut self, line: &str) {
-            if self.error.is_none() {
-                for c in line.chars() {
-                    match Cell::try_from(c) {
-                        Ok(c) => self.cells.push(c),
-                        Err(e) => {
-                            self.error = Some(e);
-                            return;
-                        }
-                    };
-                }
-                if self.height == 0 {
-                    self.width = self.cells.len();
-                }
-                self.height += 1;
+        /// Adds a row pushed by `parse`, which gets the 1-based line number.
+        fn feed_row<F>(&mut self, parse: F)
+        where
+            F: FnOnce(usize, &mut std::vec::Vec<Cell>) -> Result<(), MapParseError<E>>,
+        {
+            if self.error.is_some() {
+                return;
+            }
+            let line = self.height + 1;
+            let row_start = self.cells.len();
+            if let Err(e) = parse(line, &mut self.cells) {
+                self.error = Some(e);
+                return;
+            }
+            let found = self.cells.len() - row_start;
+            if self.height == 0 {
+                self.width = found;
+            } else if found != self.width {
+                self.error = Some(MapParseError::RaggedRow {
+                    line,
+                    expected: self.width,
+                    found,
+                });
+                return;
             }
+            self.height += 1;
         }
 
-        pub fn finalize(self) -> Result<Map<Cell>, <Cell as TryFrom<char>>::Error> {
+        fn finalize(self) -> Result<Map<Cell>, MapParseError<E>> {
             match self.error {
                 Some(e) => Err(e),
-                None => {
-                    assert_eq!(self.width * self.height, self.cells.len());
-                    Ok(Map {
-                        width: self.width,
-                        height: self.height,
-                        cells: self.cells,
-                    })
-                }
+                None => Ok(Map {
+                    width: self.width,
+                    height: self.height,
+                    cells: self.cells,
+                }),
             }
         }
     }
 
+    pub struct MapBuilder<Cell>
+    where
+        Cell: TryFrom<char>,
+    {
+        rows: MapRows<Cell, <Cell as TryFrom<char>>::Error>,
+    }
+
+    impl<Cell> MapBuilder<Cell>
+    where
+        Cell: TryFrom<char>,
+    {
+        pub fn new() -> Self {
+            Self {
+                rows: MapRows::new(),
+            }
+        }
+
+        pub fn feed_line(&mut self, line: &str) {
+            self.rows.feed_row(|line_number, cells| {
+                for (column, c) in line.chars().enumerate() {
+                    let cell = Cell::try_from(c).map_err(|error| MapParseError::InvalidCell {
+                        line: line_number,
+                        column: column + 1,
+                        c,
+                        error,
+                    })?;
+                    cells.push(cell);
+                }
+                Ok(())
+            });
+        }
+
+        pub fn finalize(self) -> Result<Map<Cell>, MapParseError<<Cell as TryFrom<char>>::Error>> {
+            self.rows.finalize()
+        }
+    }
+
     impl<Cell> FromStr for Map<Cell>
     where
         Cell: TryFrom<char>,
     {
-        type Err = <Cell as TryFrom<char>>::Error;
+        type Err = MapParseError<<Cell as TryFrom<char>>::Error>;
 
         fn from_str(s: &str) -> Result<Self, Self::Err> {
             let mut builder = MapBuilder::new();
@@ -573,4 +1049,152 @@ pub mod twod {
             builder.finalize()
         }
     }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn orientations() {
+            let map: Map<char> = "abc\ndef".parse().unwrap();
+
+            assert_eq!(
+                map.oriented(Orientation::RotateRight).to_map(),
+                map.rotate_right()
+            );
+            assert_eq!(
+                map.oriented(Orientation::HorizontalFlip).to_map(),
+                map.horizontal_flip()
+            );
+            assert_eq!(map.rotate_left().to_string(), "cf\nbe\nad");
+            assert_eq!(map.rotate_180().to_string(), "fed\ncba");
+            assert_eq!(map.vertical_flip().to_string(), "def\nabc");
+            assert_eq!(map.transpose().to_string(), "ad\nbe\ncf");
+            assert_eq!(map.anti_transpose().to_string(), "fc\neb\nda");
+            assert_eq!(map.rotate_right().rotate_left(), map);
+
+            let all: std::collections::HashSet<_> =
+                map.orientations().map(|view| view.to_map()).collect();
+            assert_eq!(all.len(), 8);
+
+            // Views are copied even when the cells can't be.
+            let words = Map::new(2, 1, String::from("ab"));
+            let view = words.oriented(Orientation::Transpose);
+            let copy = view;
+            assert_eq!(view.to_map(), copy.to_map());
+            assert_eq!(copy.width(), 1);
+        }
+
+        #[test]
+        fn metrics() {
+            let (a, b) = (Pos(1usize, 5), Pos(4usize, 1));
+            assert_eq!(a.manhattan(&b), 7);
+            assert_eq!(a.chebyshev(&b), 4);
+            assert_eq!(a.distance_squared(&b), 25);
+
+            let v: Vec<i64> = Vec(-6, 4);
+            assert_eq!(v.abs(), Vec(6, 4));
+            assert_eq!(v.manhattan_len(), 10);
+            assert_eq!(v.signum(), Vec(-1, 1));
+            assert_eq!(v.step_dir(), Some(Dir::DownLeft));
+            assert_eq!(Vec(0i64, 0).step_dir(), None);
+            assert_eq!(v.reduced(), Vec(-3, 2));
+            assert_eq!(v.dot(&Vec(1, 1)), -2);
+            assert_eq!(Vec(1i64, 0).cross(&Vec(0, 1)), 1);
+
+            assert_eq!(a.try_cast::<isize>(), Some(Pos(1isize, 5)));
+            assert_eq!(Pos(-1isize, 0).try_cast::<usize>(), None);
+            assert_eq!(Pos(0usize, 0).checked_add_dir(Dir::Up), None);
+            assert_eq!(Pos(0usize, 0).checked_add(Vec(2, 3)), Some(Pos(2, 3)));
+        }
+
+        #[test]
+        fn lines() {
+            let mut map: Map<char> = "abc\ndef".parse().unwrap();
+
+            assert_eq!(map.row(1), Some(&['d', 'e', 'f'][..]));
+            assert_eq!(map.rows().count(), 2);
+            assert_eq!(
+                map.columns()
+                    .map(|column| column.collect::<String>())
+                    .collect::<vec::Vec<_>>(),
+                ["ad", "be", "cf"]
+            );
+            assert!(map.column(3).is_none());
+
+            for (i, column) in map.columns_mut().enumerate() {
+                for cell in column {
+                    *cell = char::from(b'0' + i as u8);
+                }
+            }
+            map.row_mut(0).unwrap()[0] = 'x';
+            assert_eq!(map.to_string(), "x12\n012");
+
+            let seen: String = map.ray(Pos(2, 1), Dir::Left).map(|(_, c)| c).collect();
+            assert_eq!(seen, "10");
+            assert_eq!(
+                map.ray(Pos(0, 1), Dir::UpRight).next(),
+                Some((Pos(1, 0), &'1'))
+            );
+            assert_eq!(map.ray(Pos(0, 0), Dir::Up).count(), 0);
+
+            let mut empty: Map<char> = "\n\n".parse().unwrap();
+            assert_eq!((empty.width, empty.height), (0, 2));
+            assert_eq!(empty.row(1), Some(&[][..]));
+            assert_eq!(empty.row(2), None);
+            assert_eq!(empty.rows().count(), 2);
+            assert_eq!(empty.rows_mut().count(), 2);
+            assert_eq!(empty.columns_mut().count(), 0);
+        }
+
+        #[cfg(feature = "serde")]
+        #[test]
+        fn serde_round_trip() {
+            let map: Map<char> = "ab\ncd".parse().unwrap();
+            let json = serde_json::to_string(&(&map, Pos(1, 2), Dir::UpLeft)).unwrap();
+            assert_eq!(
+                json,
+                r#"[{"width":2,"height":2,"cells":["a","b","c","d"]},[1,2],"UpLeft"]"#
+            );
+            let (back, pos, dir): (Map<char>, Pos<i32>, Dir) = serde_json::from_str(&json).unwrap();
+            assert_eq!((back, pos, dir), (map, Pos(1, 2), Dir::UpLeft));
+
+            let err = serde_json::from_str::<Map<char>>(r#"{"width":3,"height":3,"cells":["a"]}"#)
+                .unwrap_err();
+            assert!(err.to_string().starts_with("1 cells can't fill a 3x3 map"));
+        }
+
+        #[test]
+        fn parse_errors() {
+            let map: Map<Dir> = "^>\nv<".parse().unwrap();
+            assert_eq!(map.get(Pos(1, 1)), Some(&Dir::Left));
+
+            let err = "^>\nvx".parse::<Map<Dir>>().unwrap_err();
+            assert_eq!(
+                err,
+                MapParseError::InvalidCell {
+                    line: 2,
+                    column: 2,
+                    c: 'x',
+                    error: ParseDirError("x".to_owned()),
+                }
+            );
+            assert_eq!(
+                err.to_string(),
+                "invalid cell 'x' at line 2, column 2: invalid direction \"x\""
+            );
+            assert_eq!(
+                std::error::Error::source(&err).map(|e| e.to_string()),
+                Some("invalid direction \"x\"".to_owned())
+            );
+            assert_eq!(
+                "ab\nabc".parse::<Map<char>>(),
+                Err(MapParseError::RaggedRow {
+                    line: 2,
+                    expected: 2,
+                    found: 3,
+                })
+            );
+        }
+    }
 }
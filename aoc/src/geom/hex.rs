@@ -0,0 +1,323 @@
+use super::abs;
+use std::{
+    fmt,
+    ops::{Add, AddAssign, Neg, Sub},
+    str::FromStr,
+};
+
+/// Hexagon in axial coordinates `(q, r)`, the third cube coordinate being `s = -q - r`.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Hex<T>(pub T, pub T);
+
+impl<T, U> From<(U, U)> for Hex<T>
+where
+    T: From<U>,
+{
+    fn from((q, r): (U, U)) -> Self {
+        Hex(T::from(q), T::from(r))
+    }
+}
+
+impl<T> Add<Hex<T>> for Hex<T>
+where
+    T: Add<T, Output = T>,
+{
+    type Output = Hex<T>;
+
+    fn add(self, rhs: Hex<T>) -> Self::Output {
+        Hex(self.0 + rhs.0, self.1 + rhs.1)
+    }
+}
+
+impl<T> Sub<Hex<T>> for Hex<T>
+where
+    T: Sub<T, Output = T>,
+{
+    type Output = Hex<T>;
+
+    fn sub(self, rhs: Hex<T>) -> Self::Output {
+        Hex(self.0 - rhs.0, self.1 - rhs.1)
+    }
+}
+
+impl<T> AddAssign<Hex<T>> for Hex<T>
+where
+    T: AddAssign<T>,
+{
+    fn add_assign(&mut self, rhs: Hex<T>) {
+        self.0 += rhs.0;
+        self.1 += rhs.1;
+    }
+}
+
+/// Neighbour offsets, clockwise starting from `(1, 0)`.
+const AXIAL_DIRS: [(i8, i8); 6] = [(1, 0), (0, 1), (-1, 1), (-1, 0), (0, -1), (1, -1)];
+
+impl<T> Hex<T>
+where
+    T: Copy + Ord + Add<T, Output = T> + Sub<T, Output = T> + Neg<Output = T> + From<i8>,
+{
+    pub fn s(&self) -> T {
+        -self.0 - self.1
+    }
+
+    pub fn cube(&self) -> (T, T, T) {
+        (self.0, self.1, self.s())
+    }
+
+    pub fn step<D: HexDirection>(self, dir: D) -> Self {
+        self + dir.as_hex()
+    }
+
+    pub fn neighbours(self) -> impl Iterator<Item = Self> {
+        AXIAL_DIRS.into_iter().map(move |d| self + Hex::from(d))
+    }
+
+    /// Number of steps between both hexagons.
+    pub fn distance(&self, other: &Self) -> T {
+        let (q, r, s) = (*self - *other).cube();
+        abs(q).max(abs(r)).max(abs(s))
+    }
+
+    /// Rotation by 60° clockwise around the origin.
+    pub fn rotated_right(&self) -> Self {
+        Hex(-self.1, -self.s())
+    }
+
+    /// Rotation by 60° counter-clockwise around the origin.
+    pub fn rotated_left(&self) -> Self {
+        Hex(-self.s(), -self.0)
+    }
+
+    /// Hexagons exactly `radius` steps away, going around clockwise.
+    pub fn ring(self, radius: usize) -> impl Iterator<Item = Self> {
+        let mut current = self;
+        for _ in 0..radius {
+            current = current + Hex::from(AXIAL_DIRS[4]);
+        }
+        let steps = if radius == 0 { 1 } else { 6 * radius };
+        (0..steps).map(move |i| {
+            let hex = current;
+            if let Some(side) = i.checked_div(radius) {
+                current = current + Hex::from(AXIAL_DIRS[side]);
+            }
+            hex
+        })
+    }
+
+    /// Hexagons at most `radius` steps away, by increasing distance.
+    pub fn spiral(self, radius: usize) -> impl Iterator<Item = Self> {
+        (0..=radius).flat_map(move |r| self.ring(r))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseHexDirError(pub String);
+
+impl fmt::Display for ParseHexDirError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid hex direction {:?}", self.0)
+    }
+}
+
+impl std::error::Error for ParseHexDirError {}
+
+/// The 6 directions of a hex grid, given in clockwise order.
+pub trait HexDirection: Copy + PartialEq + Sized + 'static {
+    const CLOCKWISE: [Self; 6];
+
+    /// Number of 60° clockwise turns from the `(1, 0)` offset to the first direction of `CLOCKWISE`.
+    const FIRST_AXIAL: usize;
+
+    fn index(&self) -> usize {
+        Self::CLOCKWISE.iter().position(|d| d == self).unwrap()
+    }
+
+    fn as_hex<T: From<i8>>(&self) -> Hex<T> {
+        Hex::from(AXIAL_DIRS[(self.index() + Self::FIRST_AXIAL) % 6])
+    }
+
+    fn opposite(self) -> Self {
+        Self::CLOCKWISE[(self.index() + 3) % 6]
+    }
+
+    fn rotate_right_60(self) -> Self {
+        Self::CLOCKWISE[(self.index() + 1) % 6]
+    }
+
+    fn rotate_left_60(self) -> Self {
+        Self::CLOCKWISE[(self.index() + 5) % 6]
+    }
+}
+
+/// Directions of a hex grid with pointy tops, rows being horizontal.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PointyDir {
+    East,
+    SouthEast,
+    SouthWest,
+    West,
+    NorthWest,
+    NorthEast,
+}
+
+impl HexDirection for PointyDir {
+    const CLOCKWISE: [Self; 6] = [
+        PointyDir::East,
+        PointyDir::SouthEast,
+        PointyDir::SouthWest,
+        PointyDir::West,
+        PointyDir::NorthWest,
+        PointyDir::NorthEast,
+    ];
+    const FIRST_AXIAL: usize = 0;
+}
+
+/// Directions of a hex grid with flat tops, columns being vertical.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FlatDir {
+    North,
+    NorthEast,
+    SouthEast,
+    South,
+    SouthWest,
+    NorthWest,
+}
+
+impl HexDirection for FlatDir {
+    const CLOCKWISE: [Self; 6] = [
+        FlatDir::North,
+        FlatDir::NorthEast,
+        FlatDir::SouthEast,
+        FlatDir::South,
+        FlatDir::SouthWest,
+        FlatDir::NorthWest,
+    ];
+    const FIRST_AXIAL: usize = 4;
+}
+
+impl FromStr for PointyDir {
+    type Err = ParseHexDirError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "e" | "E" => PointyDir::East,
+            "se" | "SE" => PointyDir::SouthEast,
+            "sw" | "SW" => PointyDir::SouthWest,
+            "w" | "W" => PointyDir::West,
+            "nw" | "NW" => PointyDir::NorthWest,
+            "ne" | "NE" => PointyDir::NorthEast,
+            _ => return Err(ParseHexDirError(s.to_owned())),
+        })
+    }
+}
+
+impl fmt::Display for PointyDir {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            PointyDir::East => "e",
+            PointyDir::SouthEast => "se",
+            PointyDir::SouthWest => "sw",
+            PointyDir::West => "w",
+            PointyDir::NorthWest => "nw",
+            PointyDir::NorthEast => "ne",
+        })
+    }
+}
+
+impl FromStr for FlatDir {
+    type Err = ParseHexDirError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "n" | "N" => FlatDir::North,
+            "ne" | "NE" => FlatDir::NorthEast,
+            "se" | "SE" => FlatDir::SouthEast,
+            "s" | "S" => FlatDir::South,
+            "sw" | "SW" => FlatDir::SouthWest,
+            "nw" | "NW" => FlatDir::NorthWest,
+            _ => return Err(ParseHexDirError(s.to_owned())),
+        })
+    }
+}
+
+impl fmt::Display for FlatDir {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            FlatDir::North => "n",
+            FlatDir::NorthEast => "ne",
+            FlatDir::SouthEast => "se",
+            FlatDir::South => "s",
+            FlatDir::SouthWest => "sw",
+            FlatDir::NorthWest => "nw",
+        })
+    }
+}
+
+/// Parses directions written without separators, such as `nwwswee`.
+pub fn parse_hex_path<D>(mut s: &str) -> Result<Vec<D>, ParseHexDirError>
+where
+    D: HexDirection + FromStr<Err = ParseHexDirError>,
+{
+    let mut path = Vec::new();
+    while !s.is_empty() {
+        let (dir, rest) = match s.get(..2).map(str::parse) {
+            Some(Ok(dir)) => (dir, &s[2..]),
+            _ => {
+                let len = s.chars().next().unwrap().len_utf8();
+                (s[..len].parse()?, &s[len..])
+            }
+        };
+        path.push(dir);
+        s = rest;
+    }
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn walk<D: HexDirection>(path: &[D]) -> Hex<i64> {
+        path.iter().fold(Hex(0, 0), |hex, dir| hex.step(*dir))
+    }
+
+    #[test]
+    fn paths() {
+        let parse = |s: &str| {
+            s.split(',')
+                .map(|d| d.parse::<FlatDir>().unwrap())
+                .collect::<Vec<_>>()
+        };
+        let origin = Hex(0i64, 0);
+        assert_eq!(walk(&parse("ne,ne,s,s")).distance(&origin), 2);
+        assert_eq!(walk(&parse("se,sw,se,sw,sw")).distance(&origin), 3);
+
+        let path: Vec<PointyDir> = parse_hex_path("nwwswee").unwrap();
+        assert_eq!(path.len(), 5);
+        assert_eq!(walk(&path), origin);
+        assert!(parse_hex_path::<PointyDir>("nwx").is_err());
+
+        assert_eq!(FlatDir::North.rotate_right_60(), FlatDir::NorthEast);
+        assert_eq!(PointyDir::East.opposite(), PointyDir::West);
+        for dir in PointyDir::CLOCKWISE {
+            assert_eq!(
+                dir.as_hex::<i64>().rotated_right(),
+                dir.rotate_right_60().as_hex()
+            );
+        }
+    }
+
+    #[test]
+    fn rings() {
+        let center = Hex(2i64, -1);
+        assert_eq!(center.ring(0).collect::<Vec<_>>(), [center]);
+        let ring: Vec<_> = center.ring(3).collect();
+        assert_eq!(ring.len(), 18);
+        assert!(ring.iter().all(|h| h.distance(&center) == 3));
+        assert_eq!(center.spiral(2).count(), 19);
+    }
+}
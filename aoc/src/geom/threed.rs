@@ -0,0 +1,714 @@
+use super::abs_diff;
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    iter,
+    ops::{Add, AddAssign, Mul, Neg, RangeInclusive, Sub, SubAssign},
+};
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Pos<T>(pub T, pub T, pub T);
+
+impl<T, U> From<(U, U, U)> for Pos<T>
+where
+    T: From<U>,
+{
+    fn from((x, y, z): (U, U, U)) -> Self {
+        Pos(T::from(x), T::from(y), T::from(z))
+    }
+}
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Vec<T>(pub T, pub T, pub T);
+
+impl<T, U> From<(U, U, U)> for Vec<T>
+where
+    T: From<U>,
+{
+    fn from((x, y, z): (U, U, U)) -> Self {
+        Vec(T::from(x), T::from(y), T::from(z))
+    }
+}
+
+impl<T> Vec<T>
+where
+    T: Copy,
+{
+    fn axis(&self, i: usize) -> T {
+        match i {
+            0 => self.0,
+            1 => self.1,
+            _ => self.2,
+        }
+    }
+}
+
+impl<T> Add<Vec<T>> for Pos<T>
+where
+    T: Add<T, Output = T>,
+{
+    type Output = Pos<T>;
+
+    fn add(self, rhs: Vec<T>) -> Self::Output {
+        Pos(self.0 + rhs.0, self.1 + rhs.1, self.2 + rhs.2)
+    }
+}
+
+impl<T> Sub<Pos<T>> for Pos<T>
+where
+    T: Sub<T, Output = T>,
+{
+    type Output = Vec<T>;
+
+    fn sub(self, rhs: Pos<T>) -> Self::Output {
+        Vec(self.0 - rhs.0, self.1 - rhs.1, self.2 - rhs.2)
+    }
+}
+
+impl<T> Sub<Vec<T>> for Pos<T>
+where
+    T: Sub<T, Output = T>,
+{
+    type Output = Pos<T>;
+
+    fn sub(self, rhs: Vec<T>) -> Self::Output {
+        Pos(self.0 - rhs.0, self.1 - rhs.1, self.2 - rhs.2)
+    }
+}
+
+impl<T> AddAssign<Vec<T>> for Pos<T>
+where
+    T: AddAssign<T>,
+{
+    fn add_assign(&mut self, rhs: Vec<T>) {
+        self.0 += rhs.0;
+        self.1 += rhs.1;
+        self.2 += rhs.2;
+    }
+}
+
+impl<T> Add<Vec<T>> for Vec<T>
+where
+    T: Add<T, Output = T>,
+{
+    type Output = Vec<T>;
+
+    fn add(self, rhs: Vec<T>) -> Self::Output {
+        Vec(self.0 + rhs.0, self.1 + rhs.1, self.2 + rhs.2)
+    }
+}
+
+impl<T> Sub<Vec<T>> for Vec<T>
+where
+    T: Sub<T, Output = T>,
+{
+    type Output = Vec<T>;
+
+    fn sub(self, rhs: Vec<T>) -> Self::Output {
+        Vec(self.0 - rhs.0, self.1 - rhs.1, self.2 - rhs.2)
+    }
+}
+
+impl<T> SubAssign<Vec<T>> for Vec<T>
+where
+    T: SubAssign<T>,
+{
+    fn sub_assign(&mut self, rhs: Vec<T>) {
+        self.0 -= rhs.0;
+        self.1 -= rhs.1;
+        self.2 -= rhs.2;
+    }
+}
+
+impl<T> Neg for Vec<T>
+where
+    T: Neg<Output = T>,
+{
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Vec(-self.0, -self.1, -self.2)
+    }
+}
+
+impl<T> Mul<T> for Vec<T>
+where
+    T: Mul<T, Output = T> + Clone,
+{
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Self(self.0 * rhs.clone(), self.1 * rhs.clone(), self.2 * rhs)
+    }
+}
+
+impl<T> Pos<T>
+where
+    T: Copy + Add<T, Output = T> + Sub<T, Output = T> + Ord,
+{
+    pub fn manhattan(&self, other: &Pos<T>) -> T {
+        abs_diff(self.0, other.0) + abs_diff(self.1, other.1) + abs_diff(self.2, other.2)
+    }
+}
+
+impl<T> Pos<T>
+where
+    T: Copy + Add<T, Output = T> + From<i8>,
+{
+    /// The 6 positions sharing a face with this one.
+    pub fn ortho_neighbours(self) -> impl Iterator<Item = Pos<T>> {
+        ortho_dirs().map(move |v| self + v)
+    }
+
+    /// The 26 positions sharing a face, edge or corner with this one.
+    pub fn neighbours(self) -> impl Iterator<Item = Pos<T>> {
+        all_dirs().map(move |v| self + v)
+    }
+}
+
+pub const ORTHO_DIR: [Vec<i8>; 6] = [
+    Vec(1, 0, 0),
+    Vec(-1, 0, 0),
+    Vec(0, 1, 0),
+    Vec(0, -1, 0),
+    Vec(0, 0, 1),
+    Vec(0, 0, -1),
+];
+
+pub const ALL_DIR: [Vec<i8>; 26] = {
+    let mut dirs = [Vec(0, 0, 0); 26];
+    let mut i = 0;
+    let mut n = 0;
+    while n < 27 {
+        let dir = Vec(n % 3 - 1, n / 3 % 3 - 1, n / 9 - 1);
+        if n != 13 {
+            dirs[i] = dir;
+            i += 1;
+        }
+        n += 1;
+    }
+    dirs
+};
+
+fn convert_dir<T: From<i8>>(Vec(x, y, z): Vec<i8>) -> Vec<T> {
+    Vec(T::from(x), T::from(y), T::from(z))
+}
+
+pub fn ortho_dirs<T: From<i8>>() -> impl Iterator<Item = Vec<T>> {
+    ORTHO_DIR.into_iter().map(convert_dir)
+}
+
+pub fn all_dirs<T: From<i8>>() -> impl Iterator<Item = Vec<T>> {
+    ALL_DIR.into_iter().map(convert_dir)
+}
+
+/// Rotation by multiples of 90° around the axes: each output axis is an input axis, possibly negated.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "RawRotation")
+)]
+pub struct Rotation {
+    axes: [usize; 3],
+    signs: [i8; 3],
+}
+
+/// Deserialized fields of a [`Rotation`], before checking that they describe one.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RawRotation {
+    axes: [usize; 3],
+    signs: [i8; 3],
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawRotation> for Rotation {
+    type Error = String;
+
+    fn try_from(raw: RawRotation) -> Result<Self, Self::Error> {
+        let mut sorted = raw.axes;
+        sorted.sort();
+        if sorted != [0, 1, 2] {
+            return Err(format!(
+                "axes {:?} aren't a permutation of 0, 1, 2",
+                raw.axes
+            ));
+        }
+        if raw.signs.iter().any(|s| s.abs() != 1) {
+            return Err(format!("signs {:?} must all be 1 or -1", raw.signs));
+        }
+        Ok(Rotation {
+            axes: raw.axes,
+            signs: raw.signs,
+        })
+    }
+}
+
+impl Default for Rotation {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Rotation {
+    pub const IDENTITY: Rotation = Rotation {
+        axes: [0, 1, 2],
+        signs: [1, 1, 1],
+    };
+
+    pub fn apply<T>(&self, v: Vec<T>) -> Vec<T>
+    where
+        T: Copy + Neg<Output = T>,
+    {
+        let component = |i: usize| {
+            let value = v.axis(self.axes[i]);
+            if self.signs[i] < 0 {
+                -value
+            } else {
+                value
+            }
+        };
+        Vec(component(0), component(1), component(2))
+    }
+
+    /// Rotates a position around the origin.
+    pub fn apply_pos<T>(&self, Pos(x, y, z): Pos<T>) -> Pos<T>
+    where
+        T: Copy + Neg<Output = T>,
+    {
+        let Vec(x, y, z) = self.apply(Vec(x, y, z));
+        Pos(x, y, z)
+    }
+
+    /// Rotation applying `self` then `other`.
+    pub fn then(&self, other: &Rotation) -> Rotation {
+        let mut result = Rotation::IDENTITY;
+        for i in 0..3 {
+            result.axes[i] = self.axes[other.axes[i]];
+            result.signs[i] = other.signs[i] * self.signs[other.axes[i]];
+        }
+        result
+    }
+
+    pub fn inverse(&self) -> Rotation {
+        let mut result = Rotation::IDENTITY;
+        for i in 0..3 {
+            result.axes[self.axes[i]] = i;
+            result.signs[self.axes[i]] = self.signs[i];
+        }
+        result
+    }
+}
+
+/// The 24 orientations of a cube.
+pub fn all_rotations() -> [Rotation; 24] {
+    const PERMUTATIONS: [([usize; 3], i8); 6] = [
+        ([0, 1, 2], 1),
+        ([1, 2, 0], 1),
+        ([2, 0, 1], 1),
+        ([0, 2, 1], -1),
+        ([2, 1, 0], -1),
+        ([1, 0, 2], -1),
+    ];
+    let mut result = [Rotation::IDENTITY; 24];
+    let mut i = 0;
+    for (axes, parity) in PERMUTATIONS {
+        for s in 0..8 {
+            let signs = [1 - 2 * (s & 1), 1 - (s & 2), 1 - (s & 4) / 2];
+            if parity * signs[0] * signs[1] * signs[2] == 1 {
+                result[i] = Rotation { axes, signs };
+                i += 1;
+            }
+        }
+    }
+    result
+}
+
+/// Axis-aligned box, both corners included.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cuboid<T> {
+    pub min: Pos<T>,
+    pub max: Pos<T>,
+}
+
+impl<T> Cuboid<T>
+where
+    T: Copy + Ord,
+{
+    /// Box spanning both corners, in any order.
+    pub fn new(a: Pos<T>, b: Pos<T>) -> Self {
+        Self {
+            min: Pos(a.0.min(b.0), a.1.min(b.1), a.2.min(b.2)),
+            max: Pos(a.0.max(b.0), a.1.max(b.1), a.2.max(b.2)),
+        }
+    }
+
+    /// Smallest box containing every position.
+    pub fn bounding(positions: impl IntoIterator<Item = Pos<T>>) -> Option<Self> {
+        let mut positions = positions.into_iter();
+        let first = positions.next()?;
+        Some(positions.fold(Self::new(first, first), |mut bounds, p| {
+            bounds.extend(p);
+            bounds
+        }))
+    }
+
+    /// Grows the box to contain `p`.
+    pub fn extend(&mut self, p: Pos<T>) {
+        *self = Self::new(
+            Pos(
+                self.min.0.min(p.0),
+                self.min.1.min(p.1),
+                self.min.2.min(p.2),
+            ),
+            Pos(
+                self.max.0.max(p.0),
+                self.max.1.max(p.1),
+                self.max.2.max(p.2),
+            ),
+        );
+    }
+
+    pub fn contains(&self, p: &Pos<T>) -> bool {
+        (self.min.0..=self.max.0).contains(&p.0)
+            && (self.min.1..=self.max.1).contains(&p.1)
+            && (self.min.2..=self.max.2).contains(&p.2)
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let min = Pos(
+            self.min.0.max(other.min.0),
+            self.min.1.max(other.min.1),
+            self.min.2.max(other.min.2),
+        );
+        let max = Pos(
+            self.max.0.min(other.max.0),
+            self.max.1.min(other.max.1),
+            self.max.2.min(other.max.2),
+        );
+        if min.0 <= max.0 && min.1 <= max.1 && min.2 <= max.2 {
+            Some(Self { min, max })
+        } else {
+            None
+        }
+    }
+
+    pub fn volume(&self) -> T
+    where
+        T: Add<T, Output = T> + Sub<T, Output = T> + Mul<T, Output = T> + From<i8>,
+    {
+        let size = self.max - self.min + Vec(T::from(1), T::from(1), T::from(1));
+        size.0 * size.1 * size.2
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = Pos<T>>
+    where
+        RangeInclusive<T>: Iterator<Item = T>,
+    {
+        let (min, max) = (self.min, self.max);
+        (min.2..=max.2).flat_map(move |z| {
+            (min.1..=max.1).flat_map(move |y| (min.0..=max.0).map(move |x| Pos(x, y, z)))
+        })
+    }
+}
+
+/// Dense voxel grid, indexed from the origin.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "RawMap<Cell>")
+)]
+pub struct Map<Cell> {
+    pub width: usize,
+    pub height: usize,
+    pub depth: usize,
+    pub cells: std::vec::Vec<Cell>,
+}
+
+/// Deserialized fields of a [`Map`], before checking that the cells fill it.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RawMap<Cell> {
+    width: usize,
+    height: usize,
+    depth: usize,
+    cells: std::vec::Vec<Cell>,
+}
+
+#[cfg(feature = "serde")]
+impl<Cell> TryFrom<RawMap<Cell>> for Map<Cell> {
+    type Error = String;
+
+    fn try_from(raw: RawMap<Cell>) -> Result<Self, Self::Error> {
+        let size = raw
+            .width
+            .checked_mul(raw.height)
+            .and_then(|area| area.checked_mul(raw.depth));
+        if size != Some(raw.cells.len()) {
+            return Err(format!(
+                "{} cells can't fill a {}x{}x{} map",
+                raw.cells.len(),
+                raw.width,
+                raw.height,
+                raw.depth
+            ));
+        }
+        Ok(Map {
+            width: raw.width,
+            height: raw.height,
+            depth: raw.depth,
+            cells: raw.cells,
+        })
+    }
+}
+
+impl<Cell> Map<Cell> {
+    pub fn get(&self, p: Pos<usize>) -> Option<&Cell> {
+        self.offset_from_pos(p)
+            .and_then(|offset| self.cells.get(offset))
+    }
+
+    pub fn get_mut(&mut self, p: Pos<usize>) -> Option<&mut Cell> {
+        self.offset_from_pos(p)
+            .and_then(|offset| self.cells.get_mut(offset))
+    }
+
+    pub fn offset_from_pos(&self, Pos(x, y, z): Pos<usize>) -> Option<usize> {
+        if x >= self.width || y >= self.height || z >= self.depth {
+            None
+        } else {
+            Some(x + self.width * (y + self.height * z))
+        }
+    }
+
+    fn pos_from_offset(&self, offset: usize) -> Pos<usize> {
+        Pos(
+            offset % self.width,
+            offset / self.width % self.height,
+            offset / (self.width * self.height),
+        )
+    }
+
+    pub fn enumerate_cells(&self) -> impl DoubleEndedIterator<Item = (Pos<usize>, &Cell)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (self.pos_from_offset(i), c))
+    }
+
+    /// In-bounds positions sharing a face with `p`.
+    pub fn ortho_neighbours(
+        &self,
+        Pos(x, y, z): Pos<usize>,
+    ) -> impl Iterator<Item = Pos<usize>> + '_ {
+        ortho_dirs::<isize>().filter_map(move |Vec(dx, dy, dz)| {
+            let p = Pos(
+                x.checked_add_signed(dx)?,
+                y.checked_add_signed(dy)?,
+                z.checked_add_signed(dz)?,
+            );
+            self.offset_from_pos(p).map(|_| p)
+        })
+    }
+}
+
+impl<Cell> Map<Cell>
+where
+    Cell: Clone,
+{
+    pub fn new(width: usize, height: usize, depth: usize, value: Cell) -> Self {
+        Self {
+            width,
+            height,
+            depth,
+            cells: iter::repeat_n(value, width * height * depth).collect(),
+        }
+    }
+}
+
+/// Voxels stored by position, for unbounded or mostly empty spaces.
+/// Serialized as a list of `(position, cell)` pairs, since formats like JSON only allow string keys.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound(
+        serialize = "T: serde::Serialize, Cell: serde::Serialize",
+        deserialize = "T: serde::Deserialize<'de>, Cell: serde::Deserialize<'de>"
+    ))
+)]
+pub struct SparseMap<T, Cell>
+where
+    T: Hash + Eq,
+{
+    #[cfg_attr(feature = "serde", serde(with = "cell_pairs"))]
+    pub cells: HashMap<Pos<T>, Cell>,
+}
+
+#[cfg(feature = "serde")]
+mod cell_pairs {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::{collections::HashMap, hash::Hash};
+
+    pub fn serialize<K, V, S>(cells: &HashMap<K, V>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        K: Serialize,
+        V: Serialize,
+        S: Serializer,
+    {
+        serializer.collect_seq(cells)
+    }
+
+    pub fn deserialize<'de, K, V, D>(deserializer: D) -> Result<HashMap<K, V>, D::Error>
+    where
+        K: Deserialize<'de> + Hash + Eq,
+        V: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        let pairs = std::vec::Vec::<(K, V)>::deserialize(deserializer)?;
+        Ok(pairs.into_iter().collect())
+    }
+}
+
+impl<T, Cell> Default for SparseMap<T, Cell>
+where
+    T: Hash + Eq,
+{
+    fn default() -> Self {
+        Self {
+            cells: HashMap::new(),
+        }
+    }
+}
+
+impl<T, Cell> SparseMap<T, Cell>
+where
+    T: Copy + Hash + Ord,
+{
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn get(&self, p: &Pos<T>) -> Option<&Cell> {
+        self.cells.get(p)
+    }
+
+    pub fn get_mut(&mut self, p: &Pos<T>) -> Option<&mut Cell> {
+        self.cells.get_mut(p)
+    }
+
+    pub fn insert(&mut self, p: Pos<T>, cell: Cell) -> Option<Cell> {
+        self.cells.insert(p, cell)
+    }
+
+    pub fn remove(&mut self, p: &Pos<T>) -> Option<Cell> {
+        self.cells.remove(p)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn bounds(&self) -> Option<Cuboid<T>> {
+        Cuboid::bounding(self.cells.keys().copied())
+    }
+}
+
+impl<T, Cell> FromIterator<(Pos<T>, Cell)> for SparseMap<T, Cell>
+where
+    T: Hash + Eq,
+{
+    fn from_iter<I: IntoIterator<Item = (Pos<T>, Cell)>>(iter: I) -> Self {
+        Self {
+            cells: iter.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn rotations() {
+        let rotations = all_rotations();
+        let v = Vec(1, 2, 3);
+        let images: HashSet<_> = rotations.iter().map(|r| r.apply(v)).collect();
+        assert_eq!(images.len(), 24);
+
+        for r in &rotations {
+            assert_eq!(r.then(&r.inverse()), Rotation::IDENTITY);
+            for other in &rotations {
+                assert_eq!(r.then(other).apply(v), other.apply(r.apply(v)));
+            }
+        }
+    }
+
+    #[test]
+    fn neighbours_and_boxes() {
+        assert_eq!(ALL_DIR.iter().collect::<HashSet<_>>().len(), 26);
+        assert_eq!(Pos(0i64, 0, 0).ortho_neighbours().count(), 6);
+
+        let a = Cuboid::new(Pos(0i64, 0, 0), Pos(2, 2, 2));
+        let b = Cuboid::new(Pos(3i64, 1, 1), Pos(1, 5, 5));
+        assert_eq!(a.volume(), 27);
+        assert_eq!(
+            a.intersection(&b),
+            Some(Cuboid::new(Pos(1, 1, 1), Pos(2, 2, 2)))
+        );
+        assert_eq!(a.intersection(&b).unwrap().positions().count(), 8);
+        assert_eq!(Pos(1i64, -2, 3).manhattan(&Pos(0, 0, 0)), 6);
+
+        let grid = Map::new(2, 3, 4, 0u8);
+        assert_eq!(grid.enumerate_cells().last().unwrap().0, Pos(1, 2, 3));
+        assert_eq!(grid.ortho_neighbours(Pos(0, 0, 0)).count(), 3);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_checks_size() {
+        let grid = Map::new(1, 2, 2, 'a');
+        let json = serde_json::to_string(&grid).unwrap();
+        assert_eq!(serde_json::from_str::<Map<char>>(&json).unwrap(), grid);
+
+        let bad = r#"{"width":1,"height":2,"depth":2,"cells":["a","b"]}"#;
+        let err = serde_json::from_str::<Map<char>>(bad).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("2 cells can't fill a 1x2x2 map"));
+
+        let rotation = all_rotations()[5];
+        let json = serde_json::to_string(&rotation).unwrap();
+        assert_eq!(serde_json::from_str::<Rotation>(&json).unwrap(), rotation);
+        for bad in [
+            r#"{"axes":[7,7,7],"signs":[1,1,1]}"#,
+            r#"{"axes":[0,0,1],"signs":[1,1,1]}"#,
+            r#"{"axes":[0,1,2],"signs":[3,1,1]}"#,
+        ] {
+            assert!(serde_json::from_str::<Rotation>(bad).is_err());
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_sparse_map() {
+        let mut sparse = SparseMap::<i64, char>::default();
+        sparse.cells.insert(Pos(1, -2, 3), '#');
+        let json = serde_json::to_string(&sparse).unwrap();
+        assert_eq!(json, r##"{"cells":[[[1,-2,3],"#"]]}"##);
+        assert_eq!(
+            serde_json::from_str::<SparseMap<i64, char>>(&json).unwrap(),
+            sparse
+        );
+    }
+}
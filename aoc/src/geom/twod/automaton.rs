@@ -0,0 +1,204 @@
+use super::{Dir, Map, Pos, Vec, ALL_DIR};
+use std::mem;
+
+/// How neighbours are looked up past the edges of the map.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash, Default)]
+pub enum Edges {
+    /// Cells outside of the map don't exist.
+    #[default]
+    Bounded,
+    /// The map is a torus, leaving on one side enters on the opposite one.
+    Wrapping,
+}
+
+/// Neighbouring cells handed to an automaton rule.
+pub struct Neighbours<'a, Cell> {
+    cells: [Option<&'a Cell>; 8],
+}
+
+impl<'a, Cell> Neighbours<'a, Cell> {
+    pub fn iter(&self) -> impl Iterator<Item = &'a Cell> + '_ {
+        self.cells.iter().flatten().copied()
+    }
+
+    pub fn count_if<F: Fn(&Cell) -> bool>(&self, predicate: F) -> usize {
+        self.iter().filter(|c| predicate(c)).count()
+    }
+}
+
+impl<'a, Cell> Neighbours<'a, Cell>
+where
+    Cell: PartialEq,
+{
+    pub fn count(&self, cell: &Cell) -> usize {
+        self.count_if(|c| c == cell)
+    }
+}
+
+fn neighbour_pos<Cell>(
+    map: &Map<Cell>,
+    Pos(x, y): Pos<usize>,
+    dir: Dir,
+    edges: Edges,
+) -> Option<Pos<usize>> {
+    match edges {
+        Edges::Bounded => map.neighbour(Pos(x, y), dir),
+        Edges::Wrapping => {
+            let Vec(dx, dy) = dir.as_vec::<isize>();
+            Some(Pos(
+                (x as isize + dx).rem_euclid(map.width as isize) as usize,
+                (y as isize + dy).rem_euclid(map.height as isize) as usize,
+            ))
+        }
+    }
+}
+
+/// Writes the next generation of `map` into `next`, which must have the same size.
+/// Returns whether any cell changed.
+fn step_into<Cell, F>(
+    map: &Map<Cell>,
+    next: &mut Map<Cell>,
+    neighbourhood: &[Dir],
+    edges: Edges,
+    mut rule: F,
+) -> bool
+where
+    Cell: PartialEq,
+    F: FnMut(&Cell, &Neighbours<Cell>) -> Cell,
+{
+    let mut changed = false;
+    for ((p, cell), target) in map.enumerate_cells().zip(next.cells.iter_mut()) {
+        let mut neighbours = Neighbours { cells: [None; 8] };
+        for (slot, dir) in neighbours.cells.iter_mut().zip(neighbourhood) {
+            *slot = neighbour_pos(map, p, *dir, edges).and_then(|n| map.get(n));
+        }
+        let new_cell = rule(cell, &neighbours);
+        changed |= &new_cell != cell;
+        *target = new_cell;
+    }
+    changed
+}
+
+impl<Cell> Map<Cell>
+where
+    Cell: Clone + PartialEq,
+{
+    /// Computes the next generation with the 8 surrounding cells as neighbourhood.
+    pub fn next_generation<F>(&self, edges: Edges, rule: F) -> Self
+    where
+        F: FnMut(&Cell, &Neighbours<Cell>) -> Cell,
+    {
+        let mut next = self.clone();
+        step_into(self, &mut next, &ALL_DIR, edges, rule);
+        next
+    }
+}
+
+/// Double-buffered cellular automaton, stepping a map without reallocating it.
+pub struct Automaton<Cell> {
+    current: Map<Cell>,
+    next: Map<Cell>,
+    neighbourhood: &'static [Dir],
+    edges: Edges,
+    generation: usize,
+}
+
+impl<Cell> Automaton<Cell>
+where
+    Cell: Clone + PartialEq,
+{
+    /// Bounded automaton using the 8 surrounding cells as neighbourhood.
+    pub fn new(map: Map<Cell>) -> Self {
+        Self {
+            next: map.clone(),
+            current: map,
+            neighbourhood: &ALL_DIR,
+            edges: Edges::Bounded,
+            generation: 0,
+        }
+    }
+
+    pub fn with_edges(mut self, edges: Edges) -> Self {
+        self.edges = edges;
+        self
+    }
+
+    /// Restricts neighbours to the given directions, e.g. [`super::ORTHO_DIR`].
+    pub fn with_neighbourhood(mut self, neighbourhood: &'static [Dir]) -> Self {
+        assert!(neighbourhood.len() <= 8, "Too many neighbour directions");
+        self.neighbourhood = neighbourhood;
+        self
+    }
+
+    pub fn map(&self) -> &Map<Cell> {
+        &self.current
+    }
+
+    pub fn into_map(self) -> Map<Cell> {
+        self.current
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Advances one generation. Returns `false` if the map reached a fixed point.
+    pub fn step<F>(&mut self, rule: F) -> bool
+    where
+        F: FnMut(&Cell, &Neighbours<Cell>) -> Cell,
+    {
+        let changed = step_into(
+            &self.current,
+            &mut self.next,
+            self.neighbourhood,
+            self.edges,
+            rule,
+        );
+        mem::swap(&mut self.current, &mut self.next);
+        self.generation += 1;
+        changed
+    }
+
+    /// Steps until a generation leaves the map unchanged, returning the generation of the fixed point.
+    pub fn run_until_stable<F>(&mut self, mut rule: F) -> usize
+    where
+        F: FnMut(&Cell, &Neighbours<Cell>) -> Cell,
+    {
+        while self.step(&mut rule) {}
+        self.generation - 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn life(cell: &char, neighbours: &Neighbours<char>) -> char {
+        match (cell, neighbours.count(&'#')) {
+            ('#', 2 | 3) | ('.', 3) => '#',
+            _ => '.',
+        }
+    }
+
+    #[test]
+    fn game_of_life() {
+        let blinker: Map<char> = ".....\n..#..\n..#..\n..#..\n.....".parse().unwrap();
+        let next = blinker.next_generation(Edges::Bounded, life);
+        assert_eq!(next.to_string(), ".....\n.....\n.###.\n.....\n.....");
+        assert_eq!(next.next_generation(Edges::Bounded, life), blinker);
+
+        let glider: Map<char> = ".#....\n..#...\n###...\n......\n......\n......"
+            .parse()
+            .unwrap();
+        let mut automaton = Automaton::new(glider.clone()).with_edges(Edges::Wrapping);
+        for _ in 0..24 {
+            assert!(automaton.step(life));
+        }
+        assert_eq!(automaton.map(), &glider);
+
+        let block: Map<char> = "....\n.##.\n.##.\n....".parse().unwrap();
+        let mut automaton = Automaton::new(block.clone());
+        assert_eq!(automaton.run_until_stable(life), 0);
+        assert_eq!(automaton.into_map(), block);
+    }
+}
@@ -0,0 +1,151 @@
+use super::{Map, Pos, Rect};
+use std::ops::Range;
+
+/// Shrinks sparse coordinates into a small grid, each cell standing for a block of real coordinates.
+///
+/// Every interesting coordinate gets its own row or column, each gap between two of them gets one,
+/// and a single-coordinate border surrounds everything so flood fills can start from `(0, 0)`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Compression {
+    columns: std::vec::Vec<Range<i64>>,
+    rows: std::vec::Vec<Range<i64>>,
+}
+
+fn compress_axis(mut values: std::vec::Vec<i64>) -> std::vec::Vec<Range<i64>> {
+    values.sort_unstable();
+    values.dedup();
+    let Some((&first, _)) = values.split_first() else {
+        return std::vec::Vec::new();
+    };
+    let mut ranges = std::vec::Vec::with_capacity(2 * values.len() + 1);
+    ranges.push(first - 1..first);
+    for (i, &v) in values.iter().enumerate() {
+        ranges.push(v..v + 1);
+        if let Some(&next) = values.get(i + 1) {
+            if next > v + 1 {
+                ranges.push(v + 1..next);
+            }
+        }
+    }
+    let last = values[values.len() - 1];
+    ranges.push(last + 1..last + 2);
+    ranges
+}
+
+fn find_range(ranges: &[Range<i64>], v: i64) -> Option<usize> {
+    let i = ranges.partition_point(|r| r.end <= v);
+    ranges.get(i).filter(|r| r.contains(&v)).map(|_| i)
+}
+
+impl Compression {
+    pub fn new(points: impl IntoIterator<Item = Pos<i64>>) -> Self {
+        let (xs, ys) = points.into_iter().map(|Pos(x, y)| (x, y)).unzip();
+        Self {
+            columns: compress_axis(xs),
+            rows: compress_axis(ys),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.columns.len()
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Compressed cell containing `p`, `None` if it is outside the border.
+    pub fn compress(&self, p: Pos<i64>) -> Option<Pos<usize>> {
+        Some(Pos(
+            find_range(&self.columns, p.0)?,
+            find_range(&self.rows, p.1)?,
+        ))
+    }
+
+    /// Real coordinates covered by a compressed cell.
+    pub fn expand(&self, p: Pos<usize>) -> Option<Rect<i64>> {
+        let (column, row) = (self.columns.get(p.0)?, self.rows.get(p.1)?);
+        Some(Rect::new(
+            Pos(column.start, row.start),
+            Pos(column.end - 1, row.end - 1),
+        ))
+    }
+
+    /// Number of real positions covered by a compressed cell.
+    pub fn weight(&self, p: Pos<usize>) -> i64 {
+        self.expand(p).map_or(0, |rect| rect.area())
+    }
+
+    /// Empty compressed grid.
+    pub fn map<Cell: Clone>(&self, value: Cell) -> Map<Cell> {
+        Map::new(self.width(), self.height(), value)
+    }
+
+    pub fn weights(&self) -> Map<i64> {
+        let cells = self
+            .rows
+            .iter()
+            .flat_map(|row| {
+                self.columns
+                    .iter()
+                    .map(move |column| (column.end - column.start) * (row.end - row.start))
+            })
+            .collect();
+        Map {
+            width: self.width(),
+            height: self.height(),
+            cells,
+        }
+    }
+
+    /// Real area covered by the cells of `map` matching `pred`.
+    pub fn area<Cell>(&self, map: &Map<Cell>, mut pred: impl FnMut(&Cell) -> bool) -> i64 {
+        map.enumerate_cells()
+            .filter(|(_, cell)| pred(cell))
+            .map(|(p, _)| self.weight(p))
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geom::twod::Segment;
+
+    #[test]
+    fn huge_rectangle() {
+        let corners = [
+            Pos(-1_000_000_000i64, 5),
+            Pos(2_000_000_000, 5),
+            Pos(2_000_000_000, 3_000_000_000),
+            Pos(-1_000_000_000, 3_000_000_000),
+        ];
+        let compression = Compression::new(corners);
+        assert_eq!((compression.width(), compression.height()), (5, 5));
+        assert_eq!(compression.compress(Pos(0, 100)), Some(Pos(2, 2)));
+        assert_eq!(compression.compress(Pos(2_000_000_002, 100)), None);
+
+        let mut map = compression.map(false);
+        for (a, b) in corners.iter().zip(corners.iter().cycle().skip(1)) {
+            let (a, b) = (
+                compression.compress(*a).unwrap(),
+                compression.compress(*b).unwrap(),
+            );
+            let segment = Segment::new(a.try_cast::<i64>().unwrap(), b.try_cast().unwrap());
+            segment.draw(&mut map, |c| *c = true);
+        }
+        map.cells[2 * map.width + 2] = true;
+        assert_eq!(
+            compression.area(&map, |c| *c),
+            3_000_000_001 * 2_999_999_996
+        );
+        assert_eq!(
+            compression.weights().cells.iter().sum::<i64>(),
+            3_000_000_003 * 2_999_999_998
+        );
+        assert_eq!(
+            compression.expand(Pos(0, 0)),
+            Some(Rect::new(Pos(-1_000_000_001, 4), Pos(-1_000_000_001, 4)))
+        );
+    }
+}
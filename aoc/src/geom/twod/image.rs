@@ -0,0 +1,194 @@
+use super::{Map, Pos, Rect};
+use crate::help_path;
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, BufWriter, Write},
+};
+
+pub type Rgb = [u8; 3];
+
+/// RGB picture of a grid, each cell becoming a square of `scale` pixels.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: std::vec::Vec<Rgb>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize, background: Rgb) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![background; width * height],
+        }
+    }
+
+    pub fn from_map<Cell>(
+        map: &Map<Cell>,
+        scale: usize,
+        mut color: impl FnMut(&Cell) -> Rgb,
+    ) -> Self {
+        let mut image = Self::new(map.width * scale, map.height * scale, [0; 3]);
+        for (p, cell) in map.enumerate_cells() {
+            image.fill_cell(p, scale, color(cell));
+        }
+        image
+    }
+
+    /// Picture of the bounding box of `cells`, positions without a cell using `background`.
+    pub fn from_sparse<Cell>(
+        cells: &HashMap<Pos<i64>, Cell>,
+        scale: usize,
+        background: Rgb,
+        mut color: impl FnMut(&Cell) -> Rgb,
+    ) -> Self {
+        let Some(bounds) = Rect::bounding(cells.keys().copied()) else {
+            return Self::new(0, 0, background);
+        };
+        let size = bounds.max - bounds.min;
+        let (width, height) = (size.0 as usize + 1, size.1 as usize + 1);
+        let mut image = Self::new(width * scale, height * scale, background);
+        for (p, cell) in cells {
+            let offset = *p - bounds.min;
+            image.fill_cell(
+                Pos(offset.0 as usize, offset.1 as usize),
+                scale,
+                color(cell),
+            );
+        }
+        image
+    }
+
+    fn fill_cell(&mut self, p: Pos<usize>, scale: usize, rgb: Rgb) {
+        for y in p.1 * scale..(p.1 + 1) * scale {
+            let row = y * self.width;
+            self.pixels[row + p.0 * scale..row + (p.0 + 1) * scale].fill(rgb);
+        }
+    }
+
+    /// Binary PPM, readable by most image viewers.
+    pub fn write_ppm(&self, mut out: impl Write) -> io::Result<()> {
+        write!(out, "P6\n{} {}\n255\n", self.width, self.height)?;
+        out.write_all(self.pixels.as_flattened())
+    }
+
+    /// Uncompressed PNG.
+    pub fn write_png(&self, mut out: impl Write) -> io::Result<()> {
+        let mut header = std::vec::Vec::with_capacity(13);
+        header.extend((self.width as u32).to_be_bytes());
+        header.extend((self.height as u32).to_be_bytes());
+        header.extend([8, 2, 0, 0, 0]);
+
+        let mut raw = std::vec::Vec::with_capacity(self.height * (self.width * 3 + 1));
+        for row in self.pixels.chunks(self.width.max(1)).take(self.height) {
+            raw.push(0);
+            raw.extend(row.as_flattened());
+        }
+
+        out.write_all(b"\x89PNG\r\n\x1a\n")?;
+        write_chunk(&mut out, b"IHDR", &header)?;
+        write_chunk(&mut out, b"IDAT", &zlib_stored(&raw))?;
+        write_chunk(&mut out, b"IEND", &[])
+    }
+
+    /// Saves into `path`, as a PNG if it ends in `.png` and as a PPM otherwise.
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        if path.ends_with(".png") {
+            self.write_png(&mut out)?;
+        } else {
+            self.write_ppm(&mut out)?;
+        }
+        out.flush()
+    }
+}
+
+/// Saves frames as `help/{name}_0000.png`, `help/{name}_0001.png`, ...
+pub fn write_image_frames(name: &str, frames: impl IntoIterator<Item = Image>) -> io::Result<()> {
+    for (i, frame) in frames.into_iter().enumerate() {
+        frame.save(&help_path(&format!("{name}_{i:04}"), "png")?)?;
+    }
+    Ok(())
+}
+
+fn write_chunk(out: &mut impl Write, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    out.write_all(&(data.len() as u32).to_be_bytes())?;
+    out.write_all(kind)?;
+    out.write_all(data)?;
+    let crc = crc32(kind.iter().chain(data));
+    out.write_all(&crc.to_be_bytes())
+}
+
+/// Zlib stream made of deflate blocks without compression.
+fn zlib_stored(data: &[u8]) -> std::vec::Vec<u8> {
+    let mut out = vec![0x78, 0x01];
+    let mut blocks = data.chunks(u16::MAX as usize).peekable();
+    if blocks.peek().is_none() {
+        out.extend([1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let len = block.len() as u16;
+        out.push(blocks.peek().is_none() as u8);
+        out.extend(len.to_le_bytes());
+        out.extend((!len).to_le_bytes());
+        out.extend(block);
+    }
+    out.extend(adler32(data).to_be_bytes());
+    out
+}
+
+fn crc32<'a>(bytes: impl IntoIterator<Item = &'a u8>) -> u32 {
+    !bytes.into_iter().fold(!0u32, |crc, byte| {
+        (0..8).fold(crc ^ *byte as u32, |crc, _| {
+            if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            }
+        })
+    })
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (a, b) = bytes.iter().fold((1u32, 0u32), |(a, b), byte| {
+        let a = (a + *byte as u32) % 65521;
+        (a, (b + a) % 65521)
+    });
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn export() {
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+
+        let map: Map<char> = "#.\n.#".parse().unwrap();
+        let image = Image::from_map(&map, 2, |c| if *c == '#' { [255; 3] } else { [0; 3] });
+        assert_eq!((image.width, image.height), (4, 4));
+        assert_eq!(image.pixels[4 + 1], [255; 3]);
+        assert_eq!(image.pixels[4 + 2], [0; 3]);
+
+        let mut ppm = std::vec::Vec::new();
+        image.write_ppm(&mut ppm).unwrap();
+        assert!(ppm.starts_with(b"P6\n4 4\n255\n"));
+        assert_eq!(ppm.len(), 11 + 4 * 4 * 3);
+
+        let mut png = std::vec::Vec::new();
+        image.write_png(&mut png).unwrap();
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR\0\0\0\x04\0\0\0\x04"));
+        assert!(png.ends_with(b"IEND\xae\x42\x60\x82"));
+
+        let sparse = HashMap::from([(Pos(-2i64, 3), 'a'), (Pos(1, 4), 'b')]);
+        let image = Image::from_sparse(&sparse, 1, [0; 3], |_| [1, 2, 3]);
+        assert_eq!((image.width, image.height), (4, 2));
+        assert_eq!(image.pixels[0], [1, 2, 3]);
+        assert_eq!(image.pixels[7], [1, 2, 3]);
+        assert_eq!(image.pixels[1], [0; 3]);
+    }
+}
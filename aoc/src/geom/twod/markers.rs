@@ -0,0 +1,137 @@
+use super::{Map, MapParseError, MapRows, Pos};
+
+/// Tagged positions found while parsing a map, in reading order.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Markers<Tag> {
+    pub positions: std::vec::Vec<(Pos<usize>, Tag)>,
+}
+
+impl<Tag> Default for Markers<Tag> {
+    fn default() -> Self {
+        Self {
+            positions: std::vec::Vec::new(),
+        }
+    }
+}
+
+impl<Tag: PartialEq> Markers<Tag> {
+    /// First position tagged with `tag`.
+    pub fn get(&self, tag: &Tag) -> Option<Pos<usize>> {
+        self.all(tag).next()
+    }
+
+    pub fn all<'a>(&'a self, tag: &'a Tag) -> impl Iterator<Item = Pos<usize>> + 'a {
+        self.positions
+            .iter()
+            .filter(move |(_, t)| t == tag)
+            .map(|(p, _)| *p)
+    }
+}
+
+/// Builds a map with a closure turning each char into a cell and an optional tag,
+/// or into an error saying why the char is invalid.
+pub struct MarkedMapBuilder<Cell, Tag, E, F>
+where
+    F: FnMut(char) -> Result<(Cell, Option<Tag>), E>,
+{
+    rows: MapRows<Cell, E>,
+    markers: Markers<Tag>,
+    parse: F,
+}
+
+impl<Cell, Tag, E, F> MarkedMapBuilder<Cell, Tag, E, F>
+where
+    F: FnMut(char) -> Result<(Cell, Option<Tag>), E>,
+{
+    pub fn new(parse: F) -> Self {
+        Self {
+            rows: MapRows::new(),
+            markers: Markers::default(),
+            parse,
+        }
+    }
+
+    pub fn feed_line(&mut self, line: &str) {
+        let (parse, markers) = (&mut self.parse, &mut self.markers);
+        self.rows.feed_row(|line_number, cells| {
+            for (column, c) in line.chars().enumerate() {
+                let (cell, tag) = parse(c).map_err(|error| MapParseError::InvalidCell {
+                    line: line_number,
+                    column: column + 1,
+                    c,
+                    error,
+                })?;
+                if let Some(tag) = tag {
+                    markers.positions.push((Pos(column, line_number - 1), tag));
+                }
+                cells.push(cell);
+            }
+            Ok(())
+        });
+    }
+
+    pub fn finalize(self) -> Result<(Map<Cell>, Markers<Tag>), MapParseError<E>> {
+        Ok((self.rows.finalize()?, self.markers))
+    }
+}
+
+impl<Cell> Map<Cell> {
+    /// Parses `s` with [`MarkedMapBuilder`].
+    pub fn parse_marked<Tag, E, F>(
+        s: &str,
+        parse: F,
+    ) -> Result<(Self, Markers<Tag>), MapParseError<E>>
+    where
+        F: FnMut(char) -> Result<(Cell, Option<Tag>), E>,
+    {
+        let mut builder = MarkedMapBuilder::new(parse);
+        for line in s.lines() {
+            builder.feed_line(line);
+        }
+        builder.finalize()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maze_markers() {
+        let (map, markers) = Map::parse_marked("#S.#\n#.@E\n#@.#", |c| match c {
+            '#' => Ok((false, None)),
+            '.' => Ok((true, None)),
+            'S' | 'E' | '@' => Ok((true, Some(c))),
+            _ => Err("not a maze tile"),
+        })
+        .unwrap();
+        assert_eq!((map.width, map.height), (4, 3));
+        assert_eq!(map.get(Pos(1, 0)), Some(&true));
+        assert_eq!(markers.get(&'S'), Some(Pos(1, 0)));
+        assert_eq!(markers.get(&'E'), Some(Pos(3, 1)));
+        assert_eq!(
+            markers.all(&'@').collect::<std::vec::Vec<_>>(),
+            [Pos(2, 1), Pos(1, 2)]
+        );
+        assert_eq!(markers.get(&'X'), None);
+
+        let err = Map::parse_marked("..\n.?", |c| match c {
+            '.' => Ok(((), None::<()>)),
+            _ => Err("expected '.'"),
+        })
+        .unwrap_err();
+        assert_eq!(
+            err,
+            MapParseError::InvalidCell {
+                line: 2,
+                column: 2,
+                c: '?',
+                error: "expected '.'",
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "invalid cell '?' at line 2, column 2: expected '.'"
+        );
+    }
+}
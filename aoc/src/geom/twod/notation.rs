@@ -0,0 +1,185 @@
+use super::Dir;
+use std::{fmt, str::FromStr};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDirError(pub String);
+
+impl fmt::Display for ParseDirError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid direction {:?}", self.0)
+    }
+}
+
+impl std::error::Error for ParseDirError {}
+
+/// Ways directions are commonly written in puzzle inputs.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DirNotation {
+    /// `^`, `>`, `v`, `<`, with `↗`, `↘`, `↙`, `↖` for diagonals.
+    #[default]
+    Arrows,
+    /// `U`, `R`, `D`, `L`, without diagonals.
+    Letters,
+    /// `N`, `E`, `S`, `W`, `NE`, `SE`, `SW`, `NW`.
+    Compass,
+    /// `up`, `right`, `down`, `left`, `up-right`, ...
+    Words,
+}
+
+pub const ALL_NOTATIONS: [DirNotation; 4] = [
+    DirNotation::Arrows,
+    DirNotation::Letters,
+    DirNotation::Compass,
+    DirNotation::Words,
+];
+
+impl DirNotation {
+    pub fn format(&self, dir: Dir) -> Option<&'static str> {
+        Some(match (self, dir) {
+            (DirNotation::Arrows, Dir::Up) => "^",
+            (DirNotation::Arrows, Dir::Right) => ">",
+            (DirNotation::Arrows, Dir::Down) => "v",
+            (DirNotation::Arrows, Dir::Left) => "<",
+            (DirNotation::Arrows, Dir::UpRight) => "↗",
+            (DirNotation::Arrows, Dir::DownRight) => "↘",
+            (DirNotation::Arrows, Dir::DownLeft) => "↙",
+            (DirNotation::Arrows, Dir::UpLeft) => "↖",
+            (DirNotation::Letters, Dir::Up) => "U",
+            (DirNotation::Letters, Dir::Right) => "R",
+            (DirNotation::Letters, Dir::Down) => "D",
+            (DirNotation::Letters, Dir::Left) => "L",
+            (DirNotation::Letters, _) => return None,
+            (DirNotation::Compass, Dir::Up) => "N",
+            (DirNotation::Compass, Dir::Right) => "E",
+            (DirNotation::Compass, Dir::Down) => "S",
+            (DirNotation::Compass, Dir::Left) => "W",
+            (DirNotation::Compass, Dir::UpRight) => "NE",
+            (DirNotation::Compass, Dir::DownRight) => "SE",
+            (DirNotation::Compass, Dir::DownLeft) => "SW",
+            (DirNotation::Compass, Dir::UpLeft) => "NW",
+            (DirNotation::Words, Dir::Up) => "up",
+            (DirNotation::Words, Dir::Right) => "right",
+            (DirNotation::Words, Dir::Down) => "down",
+            (DirNotation::Words, Dir::Left) => "left",
+            (DirNotation::Words, Dir::UpRight) => "up-right",
+            (DirNotation::Words, Dir::DownRight) => "down-right",
+            (DirNotation::Words, Dir::DownLeft) => "down-left",
+            (DirNotation::Words, Dir::UpLeft) => "up-left",
+        })
+    }
+
+    /// Parses a direction written in this notation, ignoring case.
+    pub fn parse(&self, s: &str) -> Option<Dir> {
+        super::all_dirs().find(|dir| {
+            self.format(*dir)
+                .is_some_and(|formatted| formatted.eq_ignore_ascii_case(s))
+        })
+    }
+
+    pub fn parse_char(&self, c: char) -> Option<Dir> {
+        self.parse(c.encode_utf8(&mut [0; 4]))
+    }
+}
+
+impl FromStr for Dir {
+    type Err = ParseDirError;
+
+    /// Accepts any of the [`DirNotation`]s.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ALL_NOTATIONS
+            .iter()
+            .find_map(|notation| notation.parse(s))
+            .ok_or_else(|| ParseDirError(s.to_owned()))
+    }
+}
+
+impl TryFrom<char> for Dir {
+    type Error = ParseDirError;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        c.encode_utf8(&mut [0; 4]).parse()
+    }
+}
+
+impl fmt::Display for Dir {
+    /// Uses [`DirNotation::Arrows`].
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(DirNotation::Arrows.format(*self).unwrap())
+    }
+}
+
+/// Relative rotation in degrees, clockwise being positive.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Turn(pub i32);
+
+impl Turn {
+    pub const LEFT: Turn = Turn(-90);
+    pub const RIGHT: Turn = Turn(90);
+    pub const AROUND: Turn = Turn(180);
+}
+
+impl FromStr for Turn {
+    type Err = ParseDirError;
+
+    /// Accepts `L`, `R`, `left` or `right`, optionally followed by an angle in degrees (`L90`, `R 270`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseDirError(s.to_owned());
+        let split = s
+            .find(|c: char| c.is_ascii_digit() || c.is_whitespace())
+            .unwrap_or(s.len());
+        let (side, degrees) = s.split_at(split);
+        let sign = match side.to_ascii_lowercase().as_str() {
+            "l" | "left" => -1,
+            "r" | "right" => 1,
+            _ => return Err(err()),
+        };
+        let degrees = match degrees.trim() {
+            "" => 90,
+            degrees => degrees.parse::<i32>().map_err(|_| err())?,
+        };
+        Ok(Turn(sign * degrees))
+    }
+}
+
+impl Dir {
+    /// Rotates by a multiple of 45°, `None` if the angle isn't one.
+    pub fn turn(self, turn: Turn) -> Option<Dir> {
+        if turn.0 % 45 != 0 {
+            return None;
+        }
+        let steps = (turn.0 / 45).rem_euclid(8);
+        Some((0..steps).fold(self, |dir, _| dir.rotate_right_45()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geom::twod::all_dirs;
+
+    #[test]
+    fn parse_and_format() {
+        for notation in ALL_NOTATIONS {
+            for dir in all_dirs() {
+                if let Some(formatted) = notation.format(dir) {
+                    assert_eq!(notation.parse(formatted), Some(dir));
+                    assert_eq!(formatted.parse(), Ok(dir));
+                }
+            }
+        }
+        assert_eq!(Dir::try_from('v'), Ok(Dir::Down));
+        assert_eq!("Left".parse(), Ok(Dir::Left));
+        assert_eq!(DirNotation::Letters.parse_char('u'), Some(Dir::Up));
+        assert!(Dir::try_from('x').is_err());
+        assert_eq!(Dir::UpLeft.to_string(), "↖");
+
+        assert_eq!("L".parse(), Ok(Turn::LEFT));
+        assert_eq!("R270".parse(), Ok(Turn(270)));
+        assert_eq!(Dir::Up.turn("R270".parse().unwrap()), Some(Dir::Left));
+        assert_eq!(Dir::Up.turn(Turn(-45)), Some(Dir::UpLeft));
+        assert_eq!(Dir::Up.turn(Turn(30)), None);
+        assert!("X90".parse::<Turn>().is_err());
+    }
+}
@@ -0,0 +1,157 @@
+use super::{Dir, Pos, Vec};
+
+/// Closed polygon on integer coordinates, the last vertex connecting back to the first.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Polygon {
+    pub vertices: std::vec::Vec<Pos<i64>>,
+}
+
+/// Builds a [`Polygon`] by walking from a starting point.
+pub struct PolygonBuilder {
+    current: Pos<i64>,
+    vertices: std::vec::Vec<Pos<i64>>,
+}
+
+impl PolygonBuilder {
+    pub fn new(start: Pos<i64>) -> Self {
+        Self {
+            current: start,
+            vertices: vec![start],
+        }
+    }
+
+    pub fn feed_step(&mut self, dir: Dir, length: i64) {
+        self.current += dir.as_vec::<i64>() * length;
+        self.vertices.push(self.current);
+    }
+
+    pub fn finalize(mut self) -> Polygon {
+        if self.vertices.len() > 1 && self.vertices.last() == self.vertices.first() {
+            self.vertices.pop();
+        }
+        Polygon {
+            vertices: self.vertices,
+        }
+    }
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 {
+        a.abs()
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+impl Polygon {
+    /// Polygon traced by the steps starting from the origin.
+    pub fn from_steps(steps: impl IntoIterator<Item = (Dir, i64)>) -> Self {
+        let mut builder = PolygonBuilder::new(Pos(0, 0));
+        for (dir, length) in steps {
+            builder.feed_step(dir, length);
+        }
+        builder.finalize()
+    }
+
+    fn edges(&self) -> impl Iterator<Item = (Pos<i64>, Pos<i64>)> + '_ {
+        self.vertices
+            .iter()
+            .zip(self.vertices.iter().cycle().skip(1))
+            .map(|(a, b)| (*a, *b))
+    }
+
+    /// Twice the area given by the shoelace formula, which is always an integer.
+    pub fn double_area(&self) -> i64 {
+        self.edges()
+            .map(|(a, b)| (a - Pos(0, 0)).cross(&(b - Pos(0, 0))))
+            .sum::<i64>()
+            .abs()
+    }
+
+    /// Area enclosed by the vertices, rounded down when it is not an integer.
+    pub fn area(&self) -> i64 {
+        self.double_area() / 2
+    }
+
+    /// Number of lattice points on the boundary, i.e. its length in steps when edges are orthogonal.
+    pub fn perimeter(&self) -> i64 {
+        self.edges()
+            .map(|(a, b)| {
+                let Vec(dx, dy) = b - a;
+                gcd(dx, dy)
+            })
+            .sum()
+    }
+
+    /// Lattice points strictly inside the polygon, using Pick's theorem.
+    pub fn interior_points(&self) -> i64 {
+        (self.double_area() - self.perimeter() + 2) / 2
+    }
+
+    /// Lattice points inside or on the boundary, e.g. the cells dug along and inside a dig plan.
+    pub fn lattice_points(&self) -> i64 {
+        self.interior_points() + self.perimeter()
+    }
+
+    pub fn on_boundary(&self, p: Pos<i64>) -> bool {
+        self.edges().any(|(a, b)| {
+            (b - a).cross(&(p - a)) == 0
+                && a.0.min(b.0) <= p.0
+                && p.0 <= a.0.max(b.0)
+                && a.1.min(b.1) <= p.1
+                && p.1 <= a.1.max(b.1)
+        })
+    }
+
+    /// Whether `p` is inside the polygon, points on the boundary being inside.
+    pub fn contains(&self, p: Pos<i64>) -> bool {
+        if self.on_boundary(p) {
+            return true;
+        }
+        let crossings = self
+            .edges()
+            .filter(|&(a, b)| (a.1 > p.1) != (b.1 > p.1))
+            .filter(|&(a, b)| {
+                let cross = (b - a).cross(&(p - a));
+                (b.1 > a.1) == (cross > 0)
+            })
+            .count();
+        crossings % 2 == 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dig_plan() {
+        let square = Polygon::from_steps([
+            (Dir::Right, 2),
+            (Dir::Down, 2),
+            (Dir::Left, 2),
+            (Dir::Up, 2),
+        ]);
+        assert_eq!(square.vertices.len(), 4);
+        assert_eq!(square.area(), 4);
+        assert_eq!(square.perimeter(), 8);
+        assert_eq!(square.interior_points(), 1);
+        assert_eq!(square.lattice_points(), 9);
+        assert!(square.contains(Pos(1, 1)));
+        assert!(square.contains(Pos(2, 1)));
+        assert!(!square.contains(Pos(3, 1)));
+
+        let l_shape = Polygon::from_steps([
+            (Dir::Right, 6),
+            (Dir::Down, 5),
+            (Dir::Left, 2),
+            (Dir::Up, 3),
+            (Dir::Left, 4),
+            (Dir::Up, 2),
+        ]);
+        assert_eq!(l_shape.area(), 6 * 2 + 2 * 3);
+        assert!(l_shape.contains(Pos(5, 4)));
+        assert!(!l_shape.contains(Pos(1, 4)));
+    }
+}
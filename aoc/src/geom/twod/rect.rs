@@ -0,0 +1,223 @@
+use super::{Pos, Vec};
+use crate::interval::RangeSet;
+use std::{
+    iter::Sum,
+    ops::{Add, Mul, RangeInclusive, Sub},
+};
+
+/// Axis-aligned rectangle, both corners included.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rect<T> {
+    pub min: Pos<T>,
+    pub max: Pos<T>,
+}
+
+impl<T> Rect<T>
+where
+    T: Copy + Ord,
+{
+    /// Rectangle spanning both corners, in any order.
+    pub fn new(a: Pos<T>, b: Pos<T>) -> Self {
+        Self {
+            min: Pos(a.0.min(b.0), a.1.min(b.1)),
+            max: Pos(a.0.max(b.0), a.1.max(b.1)),
+        }
+    }
+
+    /// Rectangle of `size` cells starting at `min`, `None` if it would be empty.
+    pub fn with_size(min: Pos<T>, size: Vec<T>) -> Option<Self>
+    where
+        T: Add<T, Output = T> + Sub<T, Output = T> + From<i8>,
+    {
+        let zero = T::from(0);
+        if size.0 <= zero || size.1 <= zero {
+            return None;
+        }
+        Some(Self {
+            min,
+            max: min + size - Vec(T::from(1), T::from(1)),
+        })
+    }
+
+    /// Smallest rectangle containing every position.
+    pub fn bounding(positions: impl IntoIterator<Item = Pos<T>>) -> Option<Self> {
+        let mut positions = positions.into_iter();
+        let first = positions.next()?;
+        Some(positions.fold(Self::new(first, first), |mut bounds, p| {
+            bounds.extend(p);
+            bounds
+        }))
+    }
+
+    /// Grows the rectangle to contain `p`.
+    pub fn extend(&mut self, p: Pos<T>) {
+        *self = Self::new(
+            Pos(self.min.0.min(p.0), self.min.1.min(p.1)),
+            Pos(self.max.0.max(p.0), self.max.1.max(p.1)),
+        );
+    }
+
+    pub fn contains(&self, p: &Pos<T>) -> bool {
+        (self.min.0..=self.max.0).contains(&p.0) && (self.min.1..=self.max.1).contains(&p.1)
+    }
+
+    pub fn contains_rect(&self, other: &Self) -> bool {
+        self.contains(&other.min) && self.contains(&other.max)
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let min = Pos(self.min.0.max(other.min.0), self.min.1.max(other.min.1));
+        let max = Pos(self.max.0.min(other.max.0), self.max.1.min(other.max.1));
+        if min.0 <= max.0 && min.1 <= max.1 {
+            Some(Self { min, max })
+        } else {
+            None
+        }
+    }
+
+    /// Smallest rectangle containing both.
+    pub fn union(&self, other: &Self) -> Self {
+        Self {
+            min: Pos(self.min.0.min(other.min.0), self.min.1.min(other.min.1)),
+            max: Pos(self.max.0.max(other.max.0), self.max.1.max(other.max.1)),
+        }
+    }
+
+    /// Splits into the columns before `x` and the ones from `x` onwards.
+    pub fn split_x(&self, x: T) -> (Option<Self>, Option<Self>)
+    where
+        T: Sub<T, Output = T> + From<i8>,
+    {
+        let before = (x > self.min.0).then(|| Self {
+            min: self.min,
+            max: Pos((x - T::from(1)).min(self.max.0), self.max.1),
+        });
+        let after = (x <= self.max.0).then(|| Self {
+            min: Pos(x.max(self.min.0), self.min.1),
+            max: self.max,
+        });
+        (before, after)
+    }
+
+    /// Splits into the rows before `y` and the ones from `y` onwards.
+    pub fn split_y(&self, y: T) -> (Option<Self>, Option<Self>)
+    where
+        T: Sub<T, Output = T> + From<i8>,
+    {
+        let before = (y > self.min.1).then(|| Self {
+            min: self.min,
+            max: Pos(self.max.0, (y - T::from(1)).min(self.max.1)),
+        });
+        let after = (y <= self.max.1).then(|| Self {
+            min: Pos(self.min.0, y.max(self.min.1)),
+            max: self.max,
+        });
+        (before, after)
+    }
+
+    /// Parts of `self` not covered by `other`, as at most 4 disjoint rectangles.
+    pub fn difference(&self, other: &Self) -> std::vec::Vec<Self>
+    where
+        T: Add<T, Output = T> + Sub<T, Output = T> + From<i8>,
+    {
+        let Some(common) = self.intersection(other) else {
+            return vec![*self];
+        };
+        let (left, rest) = self.split_x(common.min.0);
+        let (middle, right) = rest.unwrap().split_x(common.max.0 + T::from(1));
+        let (top, rest) = middle.unwrap().split_y(common.min.1);
+        let (_, bottom) = rest.unwrap().split_y(common.max.1 + T::from(1));
+        [left, right, top, bottom].into_iter().flatten().collect()
+    }
+
+    pub fn width(&self) -> T
+    where
+        T: Add<T, Output = T> + Sub<T, Output = T> + From<i8>,
+    {
+        self.max.0 - self.min.0 + T::from(1)
+    }
+
+    pub fn height(&self) -> T
+    where
+        T: Add<T, Output = T> + Sub<T, Output = T> + From<i8>,
+    {
+        self.max.1 - self.min.1 + T::from(1)
+    }
+
+    pub fn area(&self) -> T
+    where
+        T: Add<T, Output = T> + Sub<T, Output = T> + Mul<T, Output = T> + From<i8>,
+    {
+        self.width() * self.height()
+    }
+
+    /// Cells of the rectangle, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos<T>>
+    where
+        RangeInclusive<T>: Iterator<Item = T>,
+    {
+        let (min, max) = (self.min, self.max);
+        (min.1..=max.1).flat_map(move |y| (min.0..=max.0).map(move |x| Pos(x, y)))
+    }
+}
+
+/// Number of cells covered by at least one rectangle, without visiting each cell.
+pub fn union_area<T>(rects: &[Rect<T>]) -> T
+where
+    T: Copy + Ord + Add<T, Output = T> + Sub<T, Output = T> + Mul<T, Output = T> + From<i8> + Sum,
+{
+    let one = T::from(1);
+    let mut xs: std::vec::Vec<T> = rects
+        .iter()
+        .flat_map(|r| [r.min.0, r.max.0 + one])
+        .collect();
+    xs.sort();
+    xs.dedup();
+    xs.windows(2)
+        .map(|strip| {
+            let rows: RangeSet<T> = rects
+                .iter()
+                .filter(|r| r.min.0 <= strip[0] && strip[0] <= r.max.0)
+                .map(|r| r.min.1..r.max.1 + one)
+                .collect();
+            (strip[1] - strip[0]) * rows.total_len()
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn claims() {
+        let a = Rect::with_size(Pos(1i64, 3), Vec(4, 4)).unwrap();
+        let b = Rect::with_size(Pos(3i64, 1), Vec(4, 4)).unwrap();
+        let c = Rect::with_size(Pos(5i64, 5), Vec(2, 2)).unwrap();
+        assert_eq!(a.intersection(&b), Some(Rect::new(Pos(3, 3), Pos(4, 4))));
+        assert_eq!(a.intersection(&c), None);
+        assert_eq!(a.union(&b), Rect::new(Pos(1, 1), Pos(6, 6)));
+        assert_eq!(a.area(), 16);
+        assert_eq!(a.positions().count(), 16);
+        assert!(a.contains(&Pos(4, 6)));
+        assert!(!a.contains(&Pos(5, 6)));
+
+        assert_eq!(
+            a.split_x(3),
+            (
+                Some(Rect::new(Pos(1, 3), Pos(2, 6))),
+                Some(Rect::new(Pos(3, 3), Pos(4, 6)))
+            )
+        );
+        assert_eq!(a.split_y(3), (None, Some(a)));
+        let rest = a.difference(&b);
+        assert_eq!(rest.iter().map(Rect::area).sum::<i64>(), 12);
+        assert!(rest
+            .iter()
+            .all(|r| a.contains_rect(r) && r.intersection(&b).is_none()));
+
+        assert_eq!(union_area(&[a, b, c]), 16 + 16 + 4 - 4);
+        assert_eq!(union_area::<i64>(&[]), 0);
+    }
+}
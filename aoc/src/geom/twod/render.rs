@@ -0,0 +1,230 @@
+use super::{Map, Pos, Rect};
+use crate::create_help_file;
+use std::{collections::HashMap, fmt, io, io::Write};
+
+/// Terminal foreground colours.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
+
+impl Color {
+    fn ansi_code(&self) -> u8 {
+        30 + *self as u8
+    }
+}
+
+/// How a highlighted cell is drawn, unset fields keeping the cell as is.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash, Default)]
+pub struct Style {
+    pub marker: Option<char>,
+    pub color: Option<Color>,
+}
+
+/// Debugging view of a map with overlays, built with chained calls and printed with `Display`.
+pub struct Renderer<'a, Cell> {
+    map: &'a Map<Cell>,
+    styles: HashMap<Pos<usize>, Style>,
+    region: Option<Rect<usize>>,
+    rulers: bool,
+    ansi: bool,
+}
+
+impl<'a, Cell> Renderer<'a, Cell> {
+    pub fn new(map: &'a Map<Cell>) -> Self {
+        Self {
+            map,
+            styles: HashMap::new(),
+            region: None,
+            rulers: false,
+            ansi: false,
+        }
+    }
+
+    /// Applies `style` to the positions, over previous highlights.
+    pub fn highlight(
+        mut self,
+        positions: impl IntoIterator<Item = Pos<usize>>,
+        style: Style,
+    ) -> Self {
+        for p in positions {
+            let current = self.styles.entry(p).or_default();
+            current.marker = style.marker.or(current.marker);
+            current.color = style.color.or(current.color);
+        }
+        self
+    }
+
+    /// Draws an arrow towards the next position on each step of `path`, the last one being left as is.
+    pub fn path(mut self, path: &[Pos<usize>], color: Option<Color>) -> Self {
+        for (i, p) in path.iter().enumerate() {
+            let marker = path.get(i + 1).and_then(|next| {
+                let step = next.try_cast::<isize>()? - p.try_cast::<isize>()?;
+                step.step_dir()
+            });
+            let marker = marker.map(|dir| dir.to_string().chars().next().unwrap());
+            self = self.highlight([*p], Style { marker, color });
+        }
+        self
+    }
+
+    /// Only shows the cells in `region`.
+    pub fn crop(mut self, region: Rect<usize>) -> Self {
+        self.region = Some(region);
+        self
+    }
+
+    /// Adds column numbers on top and row numbers on the left, assuming cells are one char wide.
+    pub fn rulers(mut self) -> Self {
+        self.rulers = true;
+        self
+    }
+
+    /// Uses ANSI escape codes for colours, which are ignored otherwise.
+    pub fn ansi(mut self) -> Self {
+        self.ansi = true;
+        self
+    }
+
+    fn visible(&self) -> Option<Rect<usize>> {
+        let whole = Rect::new(
+            Pos(0, 0),
+            Pos(
+                self.map.width.checked_sub(1)?,
+                self.map.height.checked_sub(1)?,
+            ),
+        );
+        match self.region {
+            Some(region) => whole.intersection(&region),
+            None => Some(whole),
+        }
+    }
+}
+
+impl<Cell> fmt::Display for Renderer<'_, Cell>
+where
+    Cell: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(Rect { min, max }) = self.visible() else {
+            return Ok(());
+        };
+        let margin = max.1.to_string().len();
+        if self.rulers {
+            let digits = max.0.to_string().len();
+            for place in (0..digits as u32).rev() {
+                write!(f, "{:margin$} ", "")?;
+                for x in min.0..=max.0 {
+                    let unit = 10usize.pow(place);
+                    if x < unit && place > 0 {
+                        write!(f, " ")?;
+                    } else {
+                        write!(f, "{}", x / unit % 10)?;
+                    }
+                }
+                writeln!(f)?;
+            }
+        }
+        for y in min.1..=max.1 {
+            if y > min.1 {
+                writeln!(f)?;
+            }
+            if self.rulers {
+                write!(f, "{y:>margin$} ")?;
+            }
+            for x in min.0..=max.0 {
+                let style = self.styles.get(&Pos(x, y)).copied().unwrap_or_default();
+                let color = style.color.filter(|_| self.ansi);
+                if let Some(color) = color {
+                    write!(f, "\x1b[{}m", color.ansi_code())?;
+                }
+                match style.marker {
+                    Some(marker) => write!(f, "{marker}")?,
+                    None => write!(f, "{}", self.map.get(Pos(x, y)).unwrap())?,
+                }
+                if color.is_some() {
+                    write!(f, "\x1b[0m")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<Cell> Map<Cell> {
+    pub fn renderer(&self) -> Renderer<'_, Cell> {
+        Renderer::new(self)
+    }
+}
+
+/// Writes each frame of a simulation into the help file `name`, separated by their index.
+pub fn write_frames<T: fmt::Display>(
+    name: &str,
+    frames: impl IntoIterator<Item = T>,
+) -> io::Result<()> {
+    let mut file = io::BufWriter::new(create_help_file(name)?);
+    for (i, frame) in frames.into_iter().enumerate() {
+        writeln!(file, "-- frame {i} --\n{frame}\n")?;
+    }
+    file.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overlays() {
+        let map: Map<char> = "..........\n..........\n...#......".parse().unwrap();
+        let path = [Pos(0, 0), Pos(1, 0), Pos(1, 1), Pos(2, 2)];
+        let rendered = map
+            .renderer()
+            .path(&path, None)
+            .highlight(
+                [Pos(9, 2)],
+                Style {
+                    marker: Some('E'),
+                    color: None,
+                },
+            )
+            .rulers()
+            .to_string();
+        assert_eq!(
+            rendered,
+            "  0123456789\n0 >v........\n1 .↘........\n2 ...#.....E"
+        );
+
+        let cropped = map
+            .renderer()
+            .crop(Rect::new(Pos(2, 1), Pos(4, 5)))
+            .to_string();
+        assert_eq!(cropped, "...\n.#.");
+
+        let colored = map
+            .renderer()
+            .crop(Rect::new(Pos(3, 2), Pos(3, 2)))
+            .highlight(
+                [Pos(3, 2)],
+                Style {
+                    marker: None,
+                    color: Some(Color::Red),
+                },
+            )
+            .ansi()
+            .to_string();
+        assert_eq!(colored, "\x1b[31m#\x1b[0m");
+
+        let wide = Map::new(12, 1, '.');
+        assert_eq!(
+            wide.renderer().rulers().to_string(),
+            "            11\n  012345678901\n0 ............"
+        );
+    }
+}
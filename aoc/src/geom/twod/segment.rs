@@ -0,0 +1,243 @@
+use super::{abs, Map, Pos, Vec};
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    iter,
+    ops::{Add, Div, Mul, Neg, Rem, Sub},
+};
+
+/// Straight line between two points, both included.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Segment<T> {
+    pub start: Pos<T>,
+    pub end: Pos<T>,
+}
+
+/// Where two segments meet.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Intersection<T> {
+    /// Single crossing point at `(x / denominator, y / denominator)`, exact even off the lattice.
+    /// The fraction isn't reduced.
+    Point { x: T, y: T, denominator: T },
+    /// Collinear segments sharing a part.
+    Overlap(Segment<T>),
+}
+
+impl<T> Intersection<T>
+where
+    T: Copy + PartialEq + Div<T, Output = T> + Rem<T, Output = T> + From<i8>,
+{
+    /// The crossing point if it has integer coordinates.
+    pub fn lattice_point(&self) -> Option<Pos<T>> {
+        match *self {
+            Intersection::Point { x, y, denominator }
+                if x % denominator == T::from(0) && y % denominator == T::from(0) =>
+            {
+                Some(Pos(x / denominator, y / denominator))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<T> Segment<T>
+where
+    T: Copy + Ord + Add<T, Output = T> + Sub<T, Output = T> + Neg<Output = T> + From<i8>,
+{
+    pub fn new(start: Pos<T>, end: Pos<T>) -> Self {
+        Self { start, end }
+    }
+
+    pub fn is_horizontal(&self) -> bool {
+        self.start.1 == self.end.1
+    }
+
+    pub fn is_vertical(&self) -> bool {
+        self.start.0 == self.end.0
+    }
+
+    /// Whether the segment is at exactly 45°.
+    pub fn is_diagonal(&self) -> bool {
+        let Vec(dx, dy) = (self.end - self.start).abs();
+        dx == dy
+    }
+
+    /// Grid cells approximating the segment with Bresenham's algorithm, from start to end.
+    /// Horizontal, vertical and 45° segments give exactly the points they go through.
+    pub fn points(&self) -> impl Iterator<Item = Pos<T>> {
+        let end = self.end;
+        let Vec(dx, dy) = end - self.start;
+        let Vec(sx, sy) = Vec(dx, dy).signum();
+        let (dx, dy) = (abs(dx), -abs(dy));
+        let mut error = dx + dy;
+        let mut current = Some(self.start);
+        iter::from_fn(move || {
+            let p = current?;
+            current = if p == end {
+                None
+            } else {
+                let mut next = p;
+                let doubled = error + error;
+                if doubled >= dy {
+                    error = error + dy;
+                    next.0 = next.0 + sx;
+                }
+                if doubled <= dx {
+                    error = error + dx;
+                    next.1 = next.1 + sy;
+                }
+                Some(next)
+            };
+            Some(p)
+        })
+    }
+
+    /// Whether `p` lies exactly on the segment.
+    pub fn contains(&self, p: &Pos<T>) -> bool
+    where
+        T: Mul<T, Output = T>,
+    {
+        (self.end - self.start).cross(&(*p - self.start)) == T::from(0)
+            && self.start.0.min(self.end.0) <= p.0
+            && p.0 <= self.start.0.max(self.end.0)
+            && self.start.1.min(self.end.1) <= p.1
+            && p.1 <= self.start.1.max(self.end.1)
+    }
+
+    pub fn intersection(&self, other: &Segment<T>) -> Option<Intersection<T>>
+    where
+        T: Mul<T, Output = T>,
+    {
+        let zero = T::from(0);
+        let r = self.end - self.start;
+        let s = other.end - other.start;
+        if r == Vec(zero, zero) {
+            return other
+                .contains(&self.start)
+                .then(|| self.point_intersection());
+        }
+        if s == Vec(zero, zero) {
+            return self
+                .contains(&other.start)
+                .then(|| other.point_intersection());
+        }
+
+        let offset = other.start - self.start;
+        let mut denominator = r.cross(&s);
+        if denominator == zero {
+            if offset.cross(&r) != zero {
+                return None;
+            }
+            let key = |p: &Pos<T>| (*p - self.start).dot(&r);
+            let mut ends = [self.start, self.end, other.start, other.end];
+            ends.sort_by_key(key);
+            let overlaps = key(&self.start).max(key(&self.end))
+                >= key(&other.start).min(key(&other.end))
+                && key(&other.start).max(key(&other.end)) >= key(&self.start).min(key(&self.end));
+            return overlaps.then(|| Intersection::Overlap(Segment::new(ends[1], ends[2])));
+        }
+
+        let mut t = offset.cross(&s);
+        let mut u = offset.cross(&r);
+        if denominator < zero {
+            (denominator, t, u) = (-denominator, -t, -u);
+        }
+        if t < zero || t > denominator || u < zero || u > denominator {
+            return None;
+        }
+        Some(Intersection::Point {
+            x: self.start.0 * denominator + r.0 * t,
+            y: self.start.1 * denominator + r.1 * t,
+            denominator,
+        })
+    }
+
+    fn point_intersection(&self) -> Intersection<T> {
+        Intersection::Point {
+            x: self.start.0,
+            y: self.start.1,
+            denominator: T::from(1),
+        }
+    }
+
+    /// Applies `f` to every cell of [`Segment::points`] that is inside the map.
+    pub fn draw<Cell, F>(&self, map: &mut Map<Cell>, mut f: F)
+    where
+        usize: TryFrom<T>,
+        F: FnMut(&mut Cell),
+    {
+        for p in self.points() {
+            if let Some(cell) = p.try_cast::<usize>().and_then(|p| map.get_mut(p)) {
+                f(cell);
+            }
+        }
+    }
+
+    /// Applies `f` to every cell of [`Segment::points`], creating missing ones with their default value.
+    pub fn draw_sparse<Cell, F>(&self, cells: &mut HashMap<Pos<T>, Cell>, mut f: F)
+    where
+        T: Hash,
+        Cell: Default,
+        F: FnMut(&mut Cell),
+    {
+        for p in self.points() {
+            f(cells.entry(p).or_default());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rasterise() {
+        let diagonal = Segment::new(Pos(9i64, 7), Pos(7, 9));
+        assert!(diagonal.is_diagonal());
+        assert_eq!(
+            diagonal.points().collect::<std::vec::Vec<_>>(),
+            [Pos(9, 7), Pos(8, 8), Pos(7, 9)]
+        );
+        let steep = Segment::new(Pos(0i64, 0), Pos(1, 3));
+        assert_eq!(steep.points().count(), 4);
+        assert_eq!(steep.points().last(), Some(Pos(1, 3)));
+
+        let mut map = Map::new(10, 10, 0);
+        Segment::new(Pos(0i64, 9), Pos(5, 9)).draw(&mut map, |c| *c += 1);
+        Segment::new(Pos(3i64, 4), Pos(3, 12)).draw(&mut map, |c| *c += 1);
+        assert_eq!(map.cells.iter().filter(|c| **c == 2).count(), 1);
+
+        let mut sparse = HashMap::new();
+        diagonal.draw_sparse(&mut sparse, |c: &mut u32| *c += 1);
+        assert_eq!(sparse.len(), 3);
+    }
+
+    #[test]
+    fn intersections() {
+        let a = Segment::new(Pos(0i64, 0), Pos(4, 4));
+        let b = Segment::new(Pos(0i64, 4), Pos(4, 0));
+        assert_eq!(a.intersection(&b).unwrap().lattice_point(), Some(Pos(2, 2)));
+
+        let c = Segment::new(Pos(0i64, 1), Pos(1, 0));
+        let crossing = a.intersection(&c).unwrap();
+        assert_eq!(crossing.lattice_point(), None);
+        assert_eq!(
+            crossing,
+            Intersection::Point {
+                x: 4,
+                y: 4,
+                denominator: 8
+            }
+        );
+
+        let d = Segment::new(Pos(6i64, 6), Pos(2, 2));
+        assert_eq!(
+            a.intersection(&d),
+            Some(Intersection::Overlap(Segment::new(Pos(2, 2), Pos(4, 4))))
+        );
+        assert_eq!(a.intersection(&Segment::new(Pos(5i64, 5), Pos(6, 6))), None);
+        assert_eq!(a.intersection(&Segment::new(Pos(1i64, 0), Pos(5, 4))), None);
+    }
+}
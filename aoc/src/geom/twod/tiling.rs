@@ -0,0 +1,296 @@
+use super::{Dir, Map, Orientation, OrientedMap, Pos, ALL_ORIENTATIONS};
+
+impl<Cell> Map<Cell>
+where
+    Cell: Clone,
+{
+    pub fn split_regions(&self, width: usize, height: usize) -> Option<Vec<Self>> {
+        self.split_tiles(width, height, 0)
+    }
+
+    /// Cuts the map into `width` x `height` tiles in row-major order, neighbouring tiles sharing
+    /// `overlap` columns or rows. Fails if the tiles don't cover the map exactly.
+    pub fn split_tiles(&self, width: usize, height: usize, overlap: usize) -> Option<Vec<Self>> {
+        if width <= overlap || height <= overlap || self.width < width || self.height < height {
+            return None;
+        }
+        let step_x = width - overlap;
+        let step_y = height - overlap;
+        if !(self.width - overlap).is_multiple_of(step_x)
+            || !(self.height - overlap).is_multiple_of(step_y)
+        {
+            return None;
+        }
+
+        let tiles_per_row = (self.width - overlap) / step_x;
+        let tiles_per_column = (self.height - overlap) / step_y;
+        let mut result = Vec::with_capacity(tiles_per_row * tiles_per_column);
+        for tile_y in 0..tiles_per_column {
+            for tile_x in 0..tiles_per_row {
+                let mut cells = Vec::with_capacity(width * height);
+                for y in 0..height {
+                    let start = self
+                        .offset_from_pos(Pos(tile_x * step_x, tile_y * step_y + y))
+                        .unwrap();
+                    cells.extend_from_slice(&self.cells[start..start + width]);
+                }
+                result.push(Self {
+                    width,
+                    height,
+                    cells,
+                });
+            }
+        }
+        Some(result)
+    }
+
+    /// Copy of the map without the `border` outermost cells on each side.
+    pub fn strip_border(&self, border: usize) -> Option<Self> {
+        let width = self.width.checked_sub(2 * border)?;
+        let height = self.height.checked_sub(2 * border)?;
+        let mut cells = Vec::with_capacity(width * height);
+        for y in 0..height {
+            let start = self.offset_from_pos(Pos(border, y + border))?;
+            cells.extend_from_slice(&self.cells[start..start + width]);
+        }
+        Some(Self {
+            width,
+            height,
+            cells,
+        })
+    }
+}
+
+pub trait MapCombinable {
+    type Output;
+
+    fn combine(&self, maps_per_row: usize) -> Self::Output;
+}
+
+impl<Cell> MapCombinable for [Map<Cell>]
+where
+    Cell: Clone,
+{
+    type Output = Option<Map<Cell>>;
+
+    fn combine(&self, maps_per_row: usize) -> Self::Output {
+        combine_tiles(self, maps_per_row, 0, 0)
+    }
+}
+
+/// Reverse of [`Map::split_tiles`]: neighbouring maps share `overlap` columns or rows.
+pub fn combine_overlapping<Cell: Clone>(
+    tiles: &[Map<Cell>],
+    maps_per_row: usize,
+    overlap: usize,
+) -> Option<Map<Cell>> {
+    combine_tiles(tiles, maps_per_row, 0, overlap)
+}
+
+/// Combines the maps after removing `border` cells on each of their sides.
+pub fn combine_stripped<Cell: Clone>(
+    tiles: &[Map<Cell>],
+    maps_per_row: usize,
+    border: usize,
+) -> Option<Map<Cell>> {
+    combine_tiles(tiles, maps_per_row, border, 0)
+}
+
+fn combine_tiles<Cell: Clone>(
+    tiles: &[Map<Cell>],
+    maps_per_row: usize,
+    border: usize,
+    overlap: usize,
+) -> Option<Map<Cell>> {
+    let first = tiles.first()?;
+    if maps_per_row == 0
+        || !tiles.len().is_multiple_of(maps_per_row)
+        || tiles
+            .iter()
+            .any(|tile| tile.width != first.width || tile.height != first.height)
+    {
+        return None;
+    }
+    let inner_width = first.width.checked_sub(2 * border)?;
+    let inner_height = first.height.checked_sub(2 * border)?;
+    if inner_width <= overlap || inner_height <= overlap {
+        return None;
+    }
+
+    let step_x = inner_width - overlap;
+    let step_y = inner_height - overlap;
+    let maps_per_column = tiles.len() / maps_per_row;
+    let width = step_x * maps_per_row + overlap;
+    let height = step_y * maps_per_column + overlap;
+    let cells = (0..width * height)
+        .map(|i| {
+            let (x, y) = (i % width, i / width);
+            let tile_x = (x / step_x).min(maps_per_row - 1);
+            let tile_y = (y / step_y).min(maps_per_column - 1);
+            let local = Pos(x - tile_x * step_x + border, y - tile_y * step_y + border);
+            tiles[tile_y * maps_per_row + tile_x]
+                .get(local)
+                .unwrap()
+                .clone()
+        })
+        .collect();
+
+    Some(Map {
+        width,
+        height,
+        cells,
+    })
+}
+
+impl<'a, Cell> OrientedMap<'a, Cell> {
+    /// Cells along an orthogonal side, read left to right or top to bottom.
+    /// `None` for diagonal directions, and empty for a map without cells.
+    pub fn edge(&self, side: Dir) -> Option<Vec<&'a Cell>> {
+        let (width, height) = (self.width(), self.height());
+        if width == 0 || height == 0 {
+            return side.is_orthogonal().then(Vec::new);
+        }
+        let positions: Vec<Pos<usize>> = match side {
+            Dir::Up => (0..width).map(|x| Pos(x, 0)).collect(),
+            Dir::Down => (0..width).map(|x| Pos(x, height - 1)).collect(),
+            Dir::Left => (0..height).map(|y| Pos(0, y)).collect(),
+            Dir::Right => (0..height).map(|y| Pos(width - 1, y)).collect(),
+            _ => return None,
+        };
+        Some(
+            positions
+                .into_iter()
+                .map(|p| self.get(p).unwrap())
+                .collect(),
+        )
+    }
+}
+
+impl<'a, Cell> OrientedMap<'a, Cell>
+where
+    Cell: PartialEq,
+{
+    /// Whether `other` can be placed next to this map on the given side, never on a diagonal one.
+    pub fn fits(&self, side: Dir, other: &OrientedMap<'_, Cell>) -> bool {
+        let edge = self.edge(side);
+        edge.is_some() && edge == other.edge(side.opposite())
+    }
+}
+
+impl<Cell> Map<Cell> {
+    pub fn edge(&self, side: Dir) -> Option<Vec<&Cell>> {
+        self.oriented(Orientation::Identity).edge(side)
+    }
+}
+
+/// Solves a jigsaw of same-sized tiles: finds a placement of every tile in a `maps_per_row` wide
+/// grid, and an orientation for each, such that all touching edges match.
+///
+/// Returns the tile index and orientation of each slot in row-major order.
+pub fn assemble_tiles<Cell>(
+    tiles: &[Map<Cell>],
+    maps_per_row: usize,
+) -> Option<Vec<(usize, Orientation)>>
+where
+    Cell: PartialEq,
+{
+    if maps_per_row == 0 || !tiles.len().is_multiple_of(maps_per_row) {
+        return None;
+    }
+    let mut placed = Vec::with_capacity(tiles.len());
+    let mut used = vec![false; tiles.len()];
+    if assemble_from(tiles, maps_per_row, &mut placed, &mut used) {
+        Some(placed)
+    } else {
+        None
+    }
+}
+
+fn assemble_from<Cell: PartialEq>(
+    tiles: &[Map<Cell>],
+    maps_per_row: usize,
+    placed: &mut Vec<(usize, Orientation)>,
+    used: &mut [bool],
+) -> bool {
+    let slot = placed.len();
+    if slot == tiles.len() {
+        return true;
+    }
+    let oriented = |(i, o): (usize, Orientation)| tiles[i].oriented(o);
+    let left = (!slot.is_multiple_of(maps_per_row)).then(|| oriented(placed[slot - 1]));
+    let up = (slot >= maps_per_row).then(|| oriented(placed[slot - maps_per_row]));
+
+    for i in 0..tiles.len() {
+        if used[i] {
+            continue;
+        }
+        for orientation in ALL_ORIENTATIONS {
+            let candidate = tiles[i].oriented(orientation);
+            if left.is_some_and(|left| !left.fits(Dir::Right, &candidate))
+                || up.is_some_and(|up| !up.fits(Dir::Down, &candidate))
+            {
+                continue;
+            }
+            used[i] = true;
+            placed.push((i, orientation));
+            if assemble_from(tiles, maps_per_row, placed, used) {
+                return true;
+            }
+            placed.pop();
+            used[i] = false;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_and_combine() {
+        let map: Map<char> = "abcdef\nghijkl\nmnopqr\nstuvwx".parse().unwrap();
+
+        let tiles = map.split_regions(3, 2).unwrap();
+        assert_eq!(tiles.len(), 4);
+        assert_eq!(tiles[2].to_string(), "mno\nstu");
+        assert_eq!(tiles.combine(2), Some(map.clone()));
+        assert_eq!(tiles[..3].combine(2), None);
+
+        let overlapping = map.split_tiles(4, 3, 2).unwrap();
+        assert_eq!(overlapping.len(), 4);
+        assert_eq!(overlapping[3].to_string(), "ijkl\nopqr\nuvwx");
+        assert_eq!(combine_overlapping(&overlapping, 2, 2), Some(map.clone()));
+
+        let stripped = combine_stripped(&map.split_tiles(4, 4, 2).unwrap(), 2, 1);
+        assert_eq!(stripped.unwrap().to_string(), "hijk\nnopq");
+    }
+
+    #[test]
+    fn assemble() {
+        let map: Map<char> = "abcde\nfghij\nklmno".parse().unwrap();
+        let mut tiles = map.split_tiles(3, 2, 1).unwrap();
+        tiles.swap(0, 3);
+        tiles[1] = tiles[1].rotate_right();
+        tiles[2] = tiles[2].vertical_flip();
+
+        let placement = assemble_tiles(&tiles, 2).unwrap();
+        let assembled: Vec<_> = placement
+            .into_iter()
+            .map(|(i, o)| tiles[i].oriented(o).to_map())
+            .collect();
+        let assembled = combine_overlapping(&assembled, 2, 1).unwrap();
+        assert!(assembled.orientations().any(|view| view.to_map() == map));
+
+        assert_eq!(map.edge(Dir::Left), Some(vec![&'a', &'f', &'k']));
+        assert_eq!(map.edge(Dir::UpLeft), None);
+        let empty = Map::new(3, 0, '.');
+        assert_eq!(empty.edge(Dir::Down), Some(vec![]));
+        assert_eq!(empty.edge(Dir::Right), Some(vec![]));
+        assert_eq!(empty.edge(Dir::Up), Some(vec![]));
+        assert_eq!(empty.edge(Dir::DownLeft), None);
+        assert!(!map
+            .oriented(Orientation::Identity)
+            .fits(Dir::UpLeft, &map.oriented(Orientation::Rotate180)));
+    }
+}
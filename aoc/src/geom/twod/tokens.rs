@@ -0,0 +1,126 @@
+use super::{Map, MapParseError, MapRows};
+use std::str::FromStr;
+
+/// How a line is cut into cells, each token being trimmed before parsing.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TokenSplit<'a> {
+    /// Tokens separated by any amount of whitespace.
+    Whitespace,
+    Separator(&'a str),
+    /// Tokens of a fixed number of chars, the last one possibly shorter.
+    Width(usize),
+}
+
+impl<'a> TokenSplit<'a> {
+    pub fn split<'s>(&self, line: &'s str) -> std::vec::Vec<&'s str> {
+        match *self {
+            TokenSplit::Whitespace => line.split_whitespace().collect(),
+            TokenSplit::Separator(separator) => line.split(separator).map(str::trim).collect(),
+            TokenSplit::Width(width) => {
+                assert!(width > 0, "token width must not be 0");
+                let mut tokens = std::vec::Vec::new();
+                let mut rest = line;
+                while !rest.is_empty() {
+                    let end = rest
+                        .char_indices()
+                        .nth(width)
+                        .map_or(rest.len(), |(i, _)| i);
+                    tokens.push(rest[..end].trim());
+                    rest = &rest[end..];
+                }
+                tokens
+            }
+        }
+    }
+}
+
+/// Builds a map whose cells are parsed from tokens with [`FromStr`].
+pub struct TokenMapBuilder<'a, Cell>
+where
+    Cell: FromStr,
+{
+    rows: MapRows<Cell, <Cell as FromStr>::Err>,
+    split: TokenSplit<'a>,
+}
+
+impl<'a, Cell> TokenMapBuilder<'a, Cell>
+where
+    Cell: FromStr,
+{
+    pub fn new(split: TokenSplit<'a>) -> Self {
+        Self {
+            rows: MapRows::new(),
+            split,
+        }
+    }
+
+    pub fn feed_line(&mut self, line: &str) {
+        let split = self.split;
+        self.rows.feed_row(|line_number, cells| {
+            for (column, token) in split.split(line).into_iter().enumerate() {
+                let cell = token.parse().map_err(|error| MapParseError::InvalidToken {
+                    line: line_number,
+                    column: column + 1,
+                    token: token.to_owned(),
+                    error,
+                })?;
+                cells.push(cell);
+            }
+            Ok(())
+        });
+    }
+
+    pub fn finalize(self) -> Result<Map<Cell>, MapParseError<<Cell as FromStr>::Err>> {
+        self.rows.finalize()
+    }
+}
+
+impl<Cell> Map<Cell>
+where
+    Cell: FromStr,
+{
+    /// Parses `s` with [`TokenMapBuilder`].
+    pub fn parse_tokens(
+        s: &str,
+        split: TokenSplit,
+    ) -> Result<Self, MapParseError<<Cell as FromStr>::Err>> {
+        let mut builder = TokenMapBuilder::new(split);
+        for line in s.lines() {
+            builder.feed_line(line);
+        }
+        builder.finalize()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geom::twod::Pos;
+
+    #[test]
+    fn token_modes() {
+        let map: Map<u32> =
+            Map::parse_tokens("22 13  17\n 8  2 23", TokenSplit::Whitespace).unwrap();
+        assert_eq!((map.width, map.height), (3, 2));
+        assert_eq!(map.get(Pos(2, 1)), Some(&23));
+
+        let map: Map<i32> = Map::parse_tokens("1, -2\n3,4", TokenSplit::Separator(",")).unwrap();
+        assert_eq!(map.cells, [1, -2, 3, 4]);
+
+        let map: Map<String> = Map::parse_tokens("[A] [B]\n    [C]", TokenSplit::Width(4)).unwrap();
+        assert_eq!(map.cells, ["[A]", "[B]", "", "[C]"]);
+
+        assert!(matches!(
+            Map::<u8>::parse_tokens("1 2\n3 x", TokenSplit::Whitespace),
+            Err(MapParseError::InvalidToken { line: 2, column: 2, ref token, .. }) if token == "x"
+        ));
+        assert!(matches!(
+            Map::<u8>::parse_tokens("1 2\n3", TokenSplit::Whitespace),
+            Err(MapParseError::RaggedRow {
+                line: 2,
+                expected: 2,
+                found: 1
+            })
+        ));
+    }
+}
@@ -0,0 +1,293 @@
+use super::{Map, Pos};
+use std::fmt::{self, Display};
+
+/// Borrowed rectangular region of a [`Map`]. Positions are relative to the top left of the region.
+pub struct MapView<'a, Cell> {
+    map: &'a Map<Cell>,
+    origin: Pos<usize>,
+    width: usize,
+    height: usize,
+}
+
+impl<'a, Cell> Clone for MapView<'a, Cell> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, Cell> Copy for MapView<'a, Cell> {}
+
+/// Mutably borrowed rectangular region of a [`Map`].
+pub struct MapViewMut<'a, Cell> {
+    map: &'a mut Map<Cell>,
+    origin: Pos<usize>,
+    width: usize,
+    height: usize,
+}
+
+fn region_fits<Cell>(map: &Map<Cell>, Pos(x, y): Pos<usize>, width: usize, height: usize) -> bool {
+    x + width <= map.width && y + height <= map.height
+}
+
+fn region_rows<Cell>(
+    cells: &[Cell],
+    map_width: usize,
+    Pos(x, y): Pos<usize>,
+    width: usize,
+    height: usize,
+) -> impl DoubleEndedIterator<Item = &[Cell]> + ExactSizeIterator {
+    cells
+        .chunks(map_width.max(1))
+        .skip(y)
+        .take(height)
+        .map(move |row| &row[x..x + width])
+}
+
+impl<Cell> Map<Cell> {
+    pub fn view(
+        &self,
+        origin: Pos<usize>,
+        width: usize,
+        height: usize,
+    ) -> Option<MapView<'_, Cell>> {
+        region_fits(self, origin, width, height).then_some(MapView {
+            map: self,
+            origin,
+            width,
+            height,
+        })
+    }
+
+    pub fn view_mut(
+        &mut self,
+        origin: Pos<usize>,
+        width: usize,
+        height: usize,
+    ) -> Option<MapViewMut<'_, Cell>> {
+        if region_fits(self, origin, width, height) {
+            Some(MapViewMut {
+                map: self,
+                origin,
+                width,
+                height,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Every `width` x `height` region of the map, sliding by one cell, in row-major order.
+    pub fn windows(&self, width: usize, height: usize) -> impl Iterator<Item = MapView<'_, Cell>> {
+        let columns = (self.width + 1).saturating_sub(width);
+        let rows = (self.height + 1).saturating_sub(height);
+        (0..columns * rows).map(move |i| MapView {
+            map: self,
+            origin: Pos(i % columns, i / columns),
+            width,
+            height,
+        })
+    }
+}
+
+impl<'a, Cell> MapView<'a, Cell> {
+    pub fn origin(&self) -> Pos<usize> {
+        self.origin
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, Pos(x, y): Pos<usize>) -> Option<&'a Cell> {
+        if x >= self.width || y >= self.height {
+            None
+        } else {
+            self.map.get(Pos(self.origin.0 + x, self.origin.1 + y))
+        }
+    }
+
+    pub fn enumerate_cells(&self) -> impl DoubleEndedIterator<Item = (Pos<usize>, &'a Cell)> {
+        region_rows(
+            &self.map.cells,
+            self.map.width,
+            self.origin,
+            self.width,
+            self.height,
+        )
+        .enumerate()
+        .flat_map(|(y, row)| row.iter().enumerate().map(move |(x, c)| (Pos(x, y), c)))
+    }
+
+    /// Sub-region of this view, `origin` being relative to the view.
+    pub fn view(&self, origin: Pos<usize>, width: usize, height: usize) -> Option<Self> {
+        if origin.0 + width <= self.width && origin.1 + height <= self.height {
+            Some(Self {
+                map: self.map,
+                origin: Pos(self.origin.0 + origin.0, self.origin.1 + origin.1),
+                width,
+                height,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a, Cell> MapView<'a, Cell>
+where
+    Cell: Clone,
+{
+    pub fn to_map(&self) -> Map<Cell> {
+        Map {
+            width: self.width,
+            height: self.height,
+            cells: self.enumerate_cells().map(|(_, c)| c.clone()).collect(),
+        }
+    }
+}
+
+impl<'a, Cell> MapViewMut<'a, Cell> {
+    pub fn origin(&self) -> Pos<usize> {
+        self.origin
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn as_view(&self) -> MapView<'_, Cell> {
+        MapView {
+            map: self.map,
+            origin: self.origin,
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    pub fn get(&self, p: Pos<usize>) -> Option<&Cell> {
+        self.as_view().get(p)
+    }
+
+    pub fn get_mut(&mut self, Pos(x, y): Pos<usize>) -> Option<&mut Cell> {
+        if x >= self.width || y >= self.height {
+            None
+        } else {
+            self.map.get_mut(Pos(self.origin.0 + x, self.origin.1 + y))
+        }
+    }
+
+    pub fn enumerate_cells(&self) -> impl DoubleEndedIterator<Item = (Pos<usize>, &Cell)> {
+        self.as_view().enumerate_cells()
+    }
+
+    pub fn enumerate_cells_mut(
+        &mut self,
+    ) -> impl DoubleEndedIterator<Item = (Pos<usize>, &mut Cell)> {
+        let Pos(x, y) = self.origin;
+        let width = self.width;
+        self.map
+            .cells
+            .chunks_mut(self.map.width.max(1))
+            .skip(y)
+            .take(self.height)
+            .enumerate()
+            .flat_map(move |(row_y, row)| {
+                row[x..x + width]
+                    .iter_mut()
+                    .enumerate()
+                    .map(move |(row_x, c)| (Pos(row_x, row_y), c))
+            })
+    }
+}
+
+impl<'a, Cell> MapViewMut<'a, Cell>
+where
+    Cell: Clone,
+{
+    pub fn fill(&mut self, value: Cell) {
+        for (_, cell) in self.enumerate_cells_mut() {
+            *cell = value.clone();
+        }
+    }
+
+    pub fn to_map(&self) -> Map<Cell> {
+        self.as_view().to_map()
+    }
+}
+
+impl<'a, Cell> fmt::Display for MapView<'a, Cell>
+where
+    Cell: Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (Pos(x, y), cell) in self.enumerate_cells() {
+            if x == 0 && y > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{cell}")?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a, Cell> fmt::Display for MapViewMut<'a, Cell>
+where
+    Cell: Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_view().fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn views() {
+        let map: Map<char> = "abcd\nefgh\nijkl".parse().unwrap();
+        assert!(map.view(Pos(2, 1), 3, 1).is_none());
+        assert!(map.view(Pos(0, 2), 1, 2).is_none());
+
+        let view = map.view(Pos(1, 1), 3, 2).unwrap();
+        assert_eq!(view.to_string(), "fgh\njkl");
+        assert_eq!(view.get(Pos(2, 1)), Some(&'l'));
+        assert_eq!(view.get(Pos(3, 0)), None);
+
+        let inner = view.view(Pos(1, 1), 2, 1).unwrap();
+        assert_eq!(inner.origin(), Pos(2, 2));
+        assert_eq!(inner.to_string(), "kl");
+        assert!(view.view(Pos(1, 1), 3, 1).is_none());
+
+        let windows: std::vec::Vec<_> = map.windows(3, 2).map(|w| w.to_string()).collect();
+        assert_eq!(windows, ["abc\nefg", "bcd\nfgh", "efg\nijk", "fgh\njkl"]);
+        assert_eq!(map.windows(5, 1).count(), 0);
+    }
+
+    #[test]
+    fn mutable_views() {
+        let mut map: Map<char> = "abcd\nefgh\nijkl".parse().unwrap();
+        assert!(map.view_mut(Pos(3, 0), 2, 1).is_none());
+
+        let mut view = map.view_mut(Pos(1, 0), 2, 2).unwrap();
+        view.fill('.');
+        *view.get_mut(Pos(1, 1)).unwrap() = '#';
+        assert!(view.get_mut(Pos(2, 0)).is_none());
+        assert_eq!(view.to_string(), "..\n.#");
+        assert_eq!(map.to_string(), "a..d\ne.#h\nijkl");
+
+        let mut view = map.view_mut(Pos(2, 1), 2, 2).unwrap();
+        for (Pos(x, y), cell) in view.enumerate_cells_mut() {
+            *cell = char::from(b'0' + (x + 2 * y) as u8);
+        }
+        assert_eq!(map.to_string(), "a..d\ne.01\nij23");
+    }
+}
@@ -0,0 +1,233 @@
+use std::{
+    iter::Sum,
+    ops::{Add, Range, Sub},
+};
+
+/// Set of values stored as sorted, disjoint and non-adjacent half-open ranges.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct RangeSet<T> {
+    ranges: Vec<Range<T>>,
+}
+
+impl<T> Default for RangeSet<T> {
+    fn default() -> Self {
+        Self { ranges: Vec::new() }
+    }
+}
+
+impl<T> RangeSet<T>
+where
+    T: Copy + Ord,
+{
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn ranges(&self) -> &[Range<T>] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        let i = self.ranges.partition_point(|r| r.end <= *value);
+        self.ranges.get(i).is_some_and(|r| r.contains(value))
+    }
+
+    pub fn insert(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+        let first = self.ranges.partition_point(|r| r.end < range.start);
+        let last = self.ranges.partition_point(|r| r.start <= range.end);
+        let merged = if first < last {
+            self.ranges[first].start.min(range.start)..self.ranges[last - 1].end.max(range.end)
+        } else {
+            range
+        };
+        self.ranges.splice(first..last, [merged]);
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        self.ranges
+            .iter()
+            .chain(other.ranges.iter())
+            .cloned()
+            .collect()
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = (&self.ranges[i], &other.ranges[j]);
+            let start = a.start.max(b.start);
+            let end = a.end.min(b.end);
+            if start < end {
+                ranges.push(start..end);
+            }
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self { ranges }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let mut j = 0;
+        for range in &self.ranges {
+            let mut start = range.start;
+            while j < other.ranges.len() && other.ranges[j].end <= start {
+                j += 1;
+            }
+            let mut k = j;
+            while k < other.ranges.len() && other.ranges[k].start < range.end {
+                if start < other.ranges[k].start {
+                    ranges.push(start..other.ranges[k].start);
+                }
+                start = start.max(other.ranges[k].end);
+                k += 1;
+            }
+            if start < range.end {
+                ranges.push(start..range.end);
+            }
+        }
+        Self { ranges }
+    }
+
+    /// Number of values in the set.
+    pub fn total_len(&self) -> T
+    where
+        T: Sub<Output = T> + Sum,
+    {
+        self.ranges.iter().map(|r| r.end - r.start).sum()
+    }
+}
+
+impl<T> FromIterator<Range<T>> for RangeSet<T>
+where
+    T: Copy + Ord,
+{
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        let mut ranges: Vec<Range<T>> = iter.into_iter().filter(|r| !r.is_empty()).collect();
+        ranges.sort_by_key(|r| r.start);
+
+        let mut merged: Vec<Range<T>> = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => merged.push(range),
+            }
+        }
+        Self { ranges: merged }
+    }
+}
+
+impl<T> From<Range<T>> for RangeSet<T>
+where
+    T: Copy + Ord,
+{
+    fn from(range: Range<T>) -> Self {
+        std::iter::once(range).collect()
+    }
+}
+
+/// Piecewise translation table: values inside a piece's source range are moved to its destination,
+/// values outside of every piece are left unchanged.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct RangeMap<T> {
+    pieces: Vec<(Range<T>, T)>,
+}
+
+impl<T> Default for RangeMap<T> {
+    fn default() -> Self {
+        Self { pieces: Vec::new() }
+    }
+}
+
+impl<T> RangeMap<T>
+where
+    T: Copy + Ord + Add<Output = T> + Sub<Output = T>,
+{
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Maps `source` to the range of the same length starting at `destination`.
+    /// Pieces are expected not to overlap.
+    pub fn insert(&mut self, source: Range<T>, destination: T) {
+        self.pieces.push((source, destination));
+    }
+
+    pub fn map(&self, value: T) -> T {
+        self.pieces
+            .iter()
+            .find(|(source, _)| source.contains(&value))
+            .map_or(value, |(source, destination)| {
+                *destination + (value - source.start)
+            })
+    }
+
+    pub fn map_set(&self, set: &RangeSet<T>) -> RangeSet<T> {
+        let mut unmapped = set.clone();
+        let mut mapped = Vec::new();
+        for (source, destination) in &self.pieces {
+            let source_set = RangeSet::from(source.clone());
+            for range in set.intersection(&source_set).ranges {
+                mapped.push(
+                    *destination + (range.start - source.start)
+                        ..*destination + (range.end - source.start),
+                );
+            }
+            unmapped = unmapped.difference(&source_set);
+        }
+        mapped.into_iter().chain(unmapped.ranges).collect()
+    }
+}
+
+impl<T> FromIterator<(Range<T>, T)> for RangeMap<T> {
+    fn from_iter<I: IntoIterator<Item = (Range<T>, T)>>(iter: I) -> Self {
+        Self {
+            pieces: iter.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_operations() {
+        let a: RangeSet<i32> = [0..5, 10..15, 4..7, 7..8].into_iter().collect();
+        assert_eq!(a.ranges(), [0..8, 10..15]);
+        let b: RangeSet<i32> = [3..11, 14..20].into_iter().collect();
+
+        assert_eq!(a.union(&b), RangeSet::from(0..20));
+        assert_eq!(a.intersection(&b).ranges(), [3..8, 10..11, 14..15]);
+        assert_eq!(a.difference(&b).ranges(), [0..3, 11..14]);
+        assert_eq!(b.difference(&a).ranges(), [8..10, 15..20]);
+        assert_eq!(a.total_len(), 13);
+        assert!(a.contains(&7) && !a.contains(&8));
+
+        let mut c = a.clone();
+        c.insert(8..10);
+        assert_eq!(c, RangeSet::from(0..15));
+    }
+
+    #[test]
+    fn range_map() {
+        let seeds: RangeMap<u64> = [(98..100, 50), (50..98, 52)].into_iter().collect();
+        assert_eq!(seeds.map(79), 81);
+        assert_eq!(seeds.map(99), 51);
+        assert_eq!(seeds.map(10), 10);
+
+        let mapped = seeds.map_set(&[79..93, 40..60, 97..99].into_iter().collect());
+        assert_eq!(mapped.ranges(), [40..51, 52..62, 81..95, 99..100]);
+    }
+}
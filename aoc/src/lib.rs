@@ -1,9 +1,40 @@
 #![feature(pattern)]
 #![feature(maybe_uninit_uninit_array_transpose)]
 
+extern crate self as aoc;
+
 mod extensions;
 pub use crate::extensions::*;
+pub mod cycle;
 pub mod geom;
+pub mod interval;
+pub mod parse;
+/// Patterns and flags are checked when the crate is compiled.
+///
+/// ```
+/// aoc::static_regex! {
+///     let valid = regex("(a+)b", i);
+/// }
+/// assert!(valid.is_match("AAB"));
+/// ```
+///
+/// An invalid pattern doesn't compile:
+///
+/// ```compile_fail
+/// aoc::static_regex! {
+///     let invalid = regex("(a+b", i);
+/// }
+/// ```
+///
+/// Neither does an unknown flag:
+///
+/// ```compile_fail
+/// aoc::static_regex! {
+///     let invalid = regex("(a+)b", q);
+/// }
+/// ```
+pub use aoc_macro::static_regex;
+pub use aoc_macro::FromRegex;
 pub use regex;
 mod macros;
 
@@ -15,6 +46,7 @@ pub trait DisplayableDayResult: fmt::Display {}
 impl<T: fmt::Display> DisplayableDayResult for T {}
 
 #[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DayResult<P1: DisplayableDayResult, P2: DisplayableDayResult>(pub P1, pub P2);
 
 impl<P1: DisplayableDayResult, P2: DisplayableDayResult> fmt::Display for DayResult<P1, P2> {
@@ -124,9 +156,14 @@ pub fn exchange<T: Clone>(storage: &mut T, value: T) -> T {
     old
 }
 
-pub fn create_help_file(name: &str) -> io::Result<File> {
+/// Path of the help file `name` with the extension `ext`, creating the help directory if needed.
+pub fn help_path(name: &str, ext: &str) -> io::Result<String> {
     create_dir_all("help")?;
-    File::create(format!("help/{name}.txt"))
+    Ok(format!("help/{name}.{ext}"))
+}
+
+pub fn create_help_file(name: &str) -> io::Result<File> {
+    File::create(help_path(name, "txt")?)
 }
 
 #[cfg(test)]
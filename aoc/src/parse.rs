@@ -0,0 +1,515 @@
+use crate::StrExtensions;
+use std::{cell::RefCell, fmt, str::FromStr};
+
+/// Failure of a parser, remembering how much input was left so the position can be recovered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure {
+    remaining: usize,
+    expected: String,
+    committed: bool,
+}
+
+impl Failure {
+    /// Failure while looking at `rest`, which must be a suffix of the parsed input for the
+    /// position to be right. Other strings put the error at the start of the input.
+    pub fn new(rest: &str, expected: impl Into<String>) -> Self {
+        Self {
+            remaining: rest.len(),
+            expected: expected.into(),
+            committed: false,
+        }
+    }
+
+    /// Failure that alternatives and optional parsers don't recover from.
+    pub fn committed(rest: &str, expected: impl Into<String>) -> Self {
+        Self {
+            committed: true,
+            ..Self::new(rest, expected)
+        }
+    }
+
+    /// Keeps the failure that went furthest into the input, merging both when they are at the same place.
+    fn furthest(self, other: Self) -> Self {
+        match other.remaining.cmp(&self.remaining) {
+            std::cmp::Ordering::Less => other,
+            std::cmp::Ordering::Greater => self,
+            std::cmp::Ordering::Equal
+                if self.expected.split(" or ").any(|e| e == other.expected) =>
+            {
+                self
+            }
+            std::cmp::Ordering::Equal => Self {
+                expected: format!("{} or {}", self.expected, other.expected),
+                ..self
+            },
+        }
+    }
+}
+
+/// Failure of a whole parse, lines and columns starting at 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+}
+
+impl ParseError {
+    fn new(input: &str, failure: Failure) -> Self {
+        let consumed = input
+            .len()
+            .checked_sub(failure.remaining)
+            .and_then(|end| input.get(..end))
+            .unwrap_or("");
+        let line_start = consumed.rfind('\n').map_or(0, |i| i + 1);
+        Self {
+            line: consumed.matches('\n').count() + 1,
+            column: consumed[line_start..].chars().count() + 1,
+            expected: failure.expected,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "expected {} at line {}, column {}",
+            self.expected, self.line, self.column
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+thread_local! {
+    /// Furthest failure that a parser recovered from during the current [`Parser::parse_all`],
+    /// which explains where the input stopped making sense when some of it is left over.
+    static RECOVERED: RefCell<Option<Failure>> = const { RefCell::new(None) };
+}
+
+/// Remembers a failure that was backtracked over, e.g. the item after a separator in a list.
+fn recover(failure: Failure) {
+    RECOVERED.with_borrow_mut(|recovered| {
+        *recovered = Some(match recovered.take() {
+            Some(previous) => previous.furthest(failure),
+            None => failure,
+        })
+    });
+}
+
+/// Failure of a `FromStr` implementation generated by `#[derive(FromRegex)]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FromRegexError {
+    NoMatch(String),
+    InvalidField { field: &'static str, value: String },
+}
+
+impl fmt::Display for FromRegexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FromRegexError::NoMatch(s) => write!(f, "{s:?} does not match the pattern"),
+            FromRegexError::InvalidField { field, value } => {
+                write!(f, "invalid value {value:?} for field {field}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FromRegexError {}
+
+pub type ParseResult<'a, T> = Result<(T, &'a str), Failure>;
+
+/// Parses the start of a string, giving back the value and the rest.
+///
+/// Implemented by closures and by tuples of parsers, which run in sequence.
+pub trait Parser<'a, T> {
+    fn parse(&self, input: &'a str) -> ParseResult<'a, T>;
+
+    /// Parses the whole input, only trailing whitespace being allowed after the value.
+    ///
+    /// When input is left over, the error is the furthest failure any parser recovered from,
+    /// like an invalid item ending a list early.
+    fn parse_all(&self, input: &'a str) -> Result<T, ParseError> {
+        let outer = RECOVERED.take();
+        let result = self.parse(input);
+        let recovered = RECOVERED.replace(outer);
+        match result {
+            Ok((value, rest)) if rest.trim_end().is_empty() => Ok(value),
+            Ok((_, rest)) => {
+                let left_over = Failure::new(rest, "end of input");
+                let failure = match recovered {
+                    Some(recovered) => recovered.furthest(left_over),
+                    None => left_over,
+                };
+                Err(ParseError::new(input, failure))
+            }
+            Err(failure) => Err(ParseError::new(input, failure)),
+        }
+    }
+
+    fn map<U, F>(self, f: F) -> impl Parser<'a, U>
+    where
+        Self: Sized,
+        F: Fn(T) -> U,
+    {
+        move |input: &'a str| self.parse(input).map(|(value, rest)| (f(value), rest))
+    }
+
+    /// Tries `other` when `self` fails.
+    fn or<P>(self, other: P) -> impl Parser<'a, T>
+    where
+        Self: Sized,
+        P: Parser<'a, T>,
+    {
+        move |input: &'a str| {
+            self.parse(input).or_else(|e| {
+                if e.committed {
+                    return Err(e);
+                }
+                match other.parse(input) {
+                    Ok(parsed) => {
+                        recover(e);
+                        Ok(parsed)
+                    }
+                    Err(other) => Err(e.furthest(other)),
+                }
+            })
+        }
+    }
+}
+
+impl<'a, T, F> Parser<'a, T> for F
+where
+    F: Fn(&'a str) -> ParseResult<'a, T>,
+{
+    fn parse(&self, input: &'a str) -> ParseResult<'a, T> {
+        self(input)
+    }
+}
+
+macro_rules! impl_tuple_parser {
+    ($($parser:ident: $value:ident),+) => {
+        impl<'a, $($parser, $value),+> Parser<'a, ($($value,)+)> for ($($parser,)+)
+        where
+            $($parser: Parser<'a, $value>),+
+        {
+            #[allow(non_snake_case)]
+            fn parse(&self, input: &'a str) -> ParseResult<'a, ($($value,)+)> {
+                let ($($parser,)+) = self;
+                let rest = input;
+                $(let ($value, rest) = $parser.parse(rest)?;)+
+                Ok((($($value,)+), rest))
+            }
+        }
+    };
+}
+
+impl_tuple_parser!(A: VA, B: VB);
+impl_tuple_parser!(A: VA, B: VB, C: VC);
+impl_tuple_parser!(A: VA, B: VB, C: VC, D: VD);
+impl_tuple_parser!(A: VA, B: VB, C: VC, D: VD, E: VE);
+impl_tuple_parser!(A: VA, B: VB, C: VC, D: VD, E: VE, F: VF);
+
+pub fn literal<'a>(literal: &'static str) -> impl Parser<'a, &'a str> {
+    move |input: &'a str| match input.match_advance(literal) {
+        Some(rest) => Ok((&input[..literal.len()], rest)),
+        None => Err(Failure::new(input, format!("{literal:?}"))),
+    }
+}
+
+/// At least one char matching `predicate`.
+pub fn take_while<'a, F>(predicate: F, expected: &'static str) -> impl Parser<'a, &'a str>
+where
+    F: Fn(char) -> bool,
+{
+    move |input: &'a str| {
+        let (taken, rest) = input.consume_until(|c| !predicate(c));
+        if taken.is_empty() {
+            Err(Failure::new(input, expected))
+        } else {
+            Ok((taken, rest))
+        }
+    }
+}
+
+pub fn word<'a>() -> impl Parser<'a, &'a str> {
+    take_while(char::is_alphabetic, "a word")
+}
+
+/// Any amount of spaces and tabs, possibly none.
+pub fn spaces<'a>() -> impl Parser<'a, &'a str> {
+    move |input: &'a str| Ok(input.consume_until(|c| c != ' ' && c != '\t'))
+}
+
+fn number<'a, T: FromStr>(input: &'a str, sign: &str) -> ParseResult<'a, T> {
+    let (digits, rest) = input[sign.len()..].consume_until(|c: char| !c.is_ascii_digit());
+    if digits.is_empty() {
+        return Err(Failure::new(&input[sign.len()..], "a digit"));
+    }
+    let number = &input[..sign.len() + digits.len()];
+    match number.parse() {
+        Ok(value) => Ok((value, rest)),
+        Err(_) => Err(Failure::committed(
+            input,
+            format!("a number in range, not {number}"),
+        )),
+    }
+}
+
+pub fn unsigned<'a, T: FromStr>() -> impl Parser<'a, T> {
+    move |input: &'a str| number(input, "")
+}
+
+/// Integer with an optional `+` or `-` sign.
+pub fn signed<'a, T: FromStr>() -> impl Parser<'a, T> {
+    move |input: &'a str| {
+        let sign = if input.starts_with(['+', '-']) {
+            &input[..1]
+        } else {
+            ""
+        };
+        number(input, sign)
+    }
+}
+
+/// Never fails, giving `None` when `parser` does.
+pub fn optional<'a, T, P>(parser: P) -> impl Parser<'a, Option<T>>
+where
+    P: Parser<'a, T>,
+{
+    move |input: &'a str| match parser.parse(input) {
+        Ok((value, rest)) => Ok((Some(value), rest)),
+        Err(e) if e.committed => Err(e),
+        Err(e) => {
+            recover(e);
+            Ok((None, input))
+        }
+    }
+}
+
+/// Makes any failure of `parser` final, e.g. once a keyword identified what follows.
+pub fn cut<'a, T, P>(parser: P) -> impl Parser<'a, T>
+where
+    P: Parser<'a, T>,
+{
+    move |input: &'a str| {
+        parser.parse(input).map_err(|e| Failure {
+            committed: true,
+            ..e
+        })
+    }
+}
+
+pub fn preceded<'a, T, U, A, P>(prefix: A, parser: P) -> impl Parser<'a, T>
+where
+    A: Parser<'a, U>,
+    P: Parser<'a, T>,
+{
+    (prefix, parser).map(|(_, value)| value)
+}
+
+pub fn terminated<'a, T, U, P, B>(parser: P, suffix: B) -> impl Parser<'a, T>
+where
+    P: Parser<'a, T>,
+    B: Parser<'a, U>,
+{
+    (parser, suffix).map(|(value, _)| value)
+}
+
+/// One or more `item`s with `separator` between them, a dangling separator being left unparsed.
+pub fn separated<'a, T, U, P, S>(item: P, separator: S) -> impl Parser<'a, std::vec::Vec<T>>
+where
+    P: Parser<'a, T>,
+    S: Parser<'a, U>,
+{
+    move |input: &'a str| {
+        let (first, mut rest) = item.parse(input)?;
+        let mut items = vec![first];
+        loop {
+            match preceded(|i| separator.parse(i), |i| item.parse(i)).parse(rest) {
+                Ok((value, next)) => {
+                    items.push(value);
+                    rest = next;
+                }
+                Err(e) if e.committed => return Err(e),
+                Err(e) => {
+                    recover(e);
+                    return Ok((items, rest));
+                }
+            }
+        }
+    }
+}
+
+/// One `item` per line.
+pub fn lines<'a, T, P>(item: P) -> impl Parser<'a, std::vec::Vec<T>>
+where
+    P: Parser<'a, T>,
+{
+    separated(item, literal("\n"))
+}
+
+/// Groups separated by blank lines.
+pub fn blocks<'a, T, P>(block: P) -> impl Parser<'a, std::vec::Vec<T>>
+where
+    P: Parser<'a, T>,
+{
+    separated(block, literal("\n\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{static_regex, FromRegex};
+
+    #[derive(FromRegex, Debug, PartialEq)]
+    #[regex(r"(?P<name>\w+) \((?P<weight>\d+)\)(?: -> (?P<children>.+))?")]
+    struct Program {
+        name: String,
+        weight: u32,
+        children: Option<String>,
+    }
+
+    #[derive(FromRegex, Debug, PartialEq)]
+    enum Instruction {
+        #[regex(r"rect (\d+)x(\d+)")]
+        Rect(usize, usize),
+        #[regex(r"rotate (row|column) [xy]=(\d+) by (-?\d+)")]
+        Rotate { axis: String, index: usize, by: i32 },
+        #[regex("noop")]
+        Noop,
+    }
+
+    #[derive(FromRegex, Debug, PartialEq)]
+    enum Amount {
+        #[regex(r"(\d+)")]
+        Small(u8),
+        #[regex(r"(.+)")]
+        Other(String),
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum Color {
+        Red,
+        Blue,
+    }
+
+    #[test]
+    fn combinators() {
+        let color = literal("red")
+            .map(|_| Color::Red)
+            .or(literal("blue").map(|_| Color::Blue));
+        let cube = (signed::<i32>(), spaces(), color).map(|(n, _, c)| (n, c));
+        let game = (
+            preceded(literal("Game "), unsigned::<u32>()),
+            preceded(literal(": "), separated(cube, literal(", "))),
+        );
+        assert_eq!(
+            game.parse_all("Game 12: 3 blue, -4 red\n"),
+            Ok((12, vec![(3, Color::Blue), (-4, Color::Red)]))
+        );
+        assert_eq!(
+            game.parse_all("Game 1: 3 green").unwrap_err().to_string(),
+            "expected \"red\" or \"blue\" at line 1, column 11"
+        );
+
+        let numbers = blocks(lines(unsigned::<u8>()));
+        assert_eq!(
+            numbers.parse_all("1\n2\n\n3"),
+            Ok(vec![vec![1, 2], vec![3]])
+        );
+        let error = numbers.parse_all("1\n2\n\n300").unwrap_err();
+        assert_eq!((error.line, error.column), (4, 1));
+        assert_eq!(error.expected, "a number in range, not 300");
+
+        let list = separated(unsigned::<u32>(), literal(", "));
+        assert_eq!(
+            list.parse_all("1, 2, x").unwrap_err().to_string(),
+            "expected a digit at line 1, column 7"
+        );
+        assert_eq!(
+            list.parse_all("1, 2 3").unwrap_err().to_string(),
+            "expected \", \" or end of input at line 1, column 5"
+        );
+        let error = lines(unsigned::<u32>()).parse_all("1\n2\nx").unwrap_err();
+        assert_eq!((error.line, error.column), (3, 1));
+
+        let foreign = |_: &str| -> ParseResult<'_, ()> { Err(Failure::new("not the input", "x")) };
+        let error = foreign.parse_all("short").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 1));
+
+        let sign = (optional(literal("-")), word());
+        assert_eq!(sign.parse_all("-abc"), Ok((Some("-"), "abc")));
+        assert_eq!(sign.parse_all("abc"), Ok((None, "abc")));
+        assert!(unsigned::<u8>().parse_all("-1").is_err());
+        assert_eq!(
+            terminated(word(), literal(":")).parse("ab:c"),
+            Ok(("ab", "c"))
+        );
+    }
+
+    #[test]
+    fn from_regex() {
+        assert_eq!(
+            "fwft (72) -> ktlj, cntj".parse(),
+            Ok(Program {
+                name: "fwft".to_owned(),
+                weight: 72,
+                children: Some("ktlj, cntj".to_owned()),
+            })
+        );
+        assert_eq!("pbga (66)".parse::<Program>().unwrap().children, None);
+        assert_eq!(
+            "pbga (x)".parse::<Program>(),
+            Err(FromRegexError::NoMatch("pbga (x)".to_owned()))
+        );
+
+        assert_eq!("rect 3x2".parse(), Ok(Instruction::Rect(3, 2)));
+        assert_eq!(
+            "rotate row y=0 by -4".parse(),
+            Ok(Instruction::Rotate {
+                axis: "row".to_owned(),
+                index: 0,
+                by: -4
+            })
+        );
+        assert_eq!("noop".parse(), Ok(Instruction::Noop));
+        assert_eq!(
+            "rect 3x99999999999999999999999".parse::<Instruction>(),
+            Err(FromRegexError::InvalidField {
+                field: "1",
+                value: "99999999999999999999999".to_owned()
+            })
+        );
+
+        assert_eq!("200".parse(), Ok(Amount::Small(200)));
+        assert_eq!("300".parse(), Ok(Amount::Other("300".to_owned())));
+    }
+
+    static_regex! {
+        static NUMBER = regex(r"-?\d+");
+    }
+
+    fn numbers(s: &str) -> std::vec::Vec<&str> {
+        NUMBER.find_iter(s).map(|m| m.as_str()).collect()
+    }
+
+    #[test]
+    fn static_regexes() {
+        static_regex! {
+            let word = regex("^ hello (world)$", i, x);
+            let keywords = regex_set(["^on", "^off", "toggle"]);
+        }
+        assert!(word.is_match("HelloWORLD"));
+        assert_eq!(
+            keywords
+                .matches("on toggle")
+                .into_iter()
+                .collect::<std::vec::Vec<_>>(),
+            [0, 2]
+        );
+        assert_eq!(numbers("x=-1, y=23"), ["-1", "23"]);
+        assert_eq!(NUMBER.find_iter("1 2 3").count(), 3);
+    }
+}
@@ -0,0 +1,198 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, quote_spanned};
+use regex::Regex;
+use syn::{
+    spanned::Spanned, Attribute, Data, DeriveInput, Error, Fields, GenericArgument, LitStr,
+    PathArguments, Type,
+};
+
+/// Pattern of a `#[regex("...")]` attribute, checked and anchored to the whole string.
+struct Pattern {
+    anchored: String,
+    regex: Regex,
+    span: Span,
+}
+
+fn find_pattern(attrs: &[Attribute], span: Span) -> syn::Result<Pattern> {
+    let attr = attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("regex"))
+        .ok_or_else(|| Error::new(span, "missing #[regex(\"...\")] attribute"))?;
+    let lit: LitStr = attr.parse_args()?;
+    let regex = Regex::new(&lit.value()).map_err(|e| Error::new(lit.span(), e.to_string()))?;
+    let anchored = format!("^(?:{})$", regex.as_str());
+    Ok(Pattern {
+        anchored,
+        regex,
+        span: lit.span(),
+    })
+}
+
+/// Inner type if `ty` is written `Option<T>`.
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let last = path.path.segments.last()?;
+    if last.ident != "Option" {
+        return None;
+    }
+    match &last.arguments {
+        PathArguments::AngleBracketed(args) => match args.args.first()? {
+            GenericArgument::Type(inner) => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Expression building `constructor` from the captures `caps` of `pattern`.
+fn construct(
+    constructor: TokenStream,
+    fields: &Fields,
+    pattern: &Pattern,
+) -> syn::Result<TokenStream> {
+    let named_groups: Vec<&str> = pattern.regex.capture_names().flatten().collect();
+    let group_count = pattern.regex.captures_len() - 1;
+    let positional = named_groups.is_empty();
+    if positional && group_count != fields.len() {
+        return Err(Error::new(
+            pattern.span,
+            format!(
+                "pattern has {group_count} capture groups but there are {} fields",
+                fields.len()
+            ),
+        ));
+    }
+
+    let mut values = Vec::new();
+    for (i, field) in fields.iter().enumerate() {
+        let name = field
+            .ident
+            .as_ref()
+            .map_or_else(|| i.to_string(), |ident| ident.to_string());
+        let group = if positional {
+            let index = i + 1;
+            quote! { caps.get(#index) }
+        } else {
+            let ident = field.ident.as_ref().ok_or_else(|| {
+                Error::new(pattern.span, "tuple fields need positional capture groups")
+            })?;
+            let group = ident.to_string();
+            if !named_groups.contains(&group.as_str()) {
+                return Err(Error::new(
+                    pattern.span,
+                    format!("pattern has no capture group named `{group}`"),
+                ));
+            }
+            quote! { caps.name(#group) }
+        };
+        let parse = |ty: &Type| {
+            quote_spanned! {ty.span()=>
+                <#ty as ::std::str::FromStr>::from_str(m.as_str()).map_err(|_| {
+                    ::aoc::parse::FromRegexError::InvalidField {
+                        field: #name,
+                        value: m.as_str().to_owned(),
+                    }
+                })
+            }
+        };
+        let value = match option_inner(&field.ty) {
+            Some(inner) => {
+                let parse = parse(inner);
+                quote! {
+                    match #group {
+                        Some(m) => Some(#parse?),
+                        None => None,
+                    }
+                }
+            }
+            None => {
+                let parse = parse(&field.ty);
+                quote! {
+                    {
+                        let m = #group.ok_or(::aoc::parse::FromRegexError::InvalidField {
+                            field: #name,
+                            value: ::std::string::String::new(),
+                        })?;
+                        #parse?
+                    }
+                }
+            }
+        };
+        values.push(match &field.ident {
+            Some(ident) => quote! { #ident: #value },
+            None => value,
+        });
+    }
+
+    Ok(match fields {
+        Fields::Named(_) => quote! { #constructor { #(#values),* } },
+        Fields::Unnamed(_) => quote! { #constructor ( #(#values),* ) },
+        Fields::Unit => constructor,
+    })
+}
+
+/// Block returning from `from_str` if the string matches `pattern` and every field parses,
+/// keeping the first field error in `invalid` otherwise.
+fn try_pattern(
+    constructor: TokenStream,
+    fields: &Fields,
+    pattern: &Pattern,
+    index: usize,
+) -> syn::Result<TokenStream> {
+    let anchored = &pattern.anchored;
+    let regex = format_ident!("PATTERN_{index}");
+    let value = construct(constructor, fields, pattern)?;
+    Ok(quote! {
+        {
+            static #regex: ::std::sync::OnceLock<::aoc::regex::Regex> = ::std::sync::OnceLock::new();
+            let regex = #regex.get_or_init(|| ::aoc::regex::Regex::new(#anchored).unwrap());
+            if let Some(caps) = regex.captures(s) {
+                let value = (|| -> ::std::result::Result<Self, ::aoc::parse::FromRegexError> {
+                    Ok(#value)
+                })();
+                match value {
+                    Ok(value) => return Ok(value),
+                    Err(e) => {
+                        invalid.get_or_insert(e);
+                    }
+                }
+            }
+        }
+    })
+}
+
+pub fn derive(input: DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let attempts = match &input.data {
+        Data::Struct(data) => {
+            let pattern = find_pattern(&input.attrs, name.span())?;
+            vec![try_pattern(quote! { Self }, &data.fields, &pattern, 0)?]
+        }
+        Data::Enum(data) => data
+            .variants
+            .iter()
+            .enumerate()
+            .map(|(i, variant)| {
+                let pattern = find_pattern(&variant.attrs, variant.ident.span())?;
+                let ident = &variant.ident;
+                try_pattern(quote! { Self::#ident }, &variant.fields, &pattern, i)
+            })
+            .collect::<syn::Result<_>>()?,
+        Data::Union(_) => return Err(Error::new(name.span(), "FromRegex does not support unions")),
+    };
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::std::str::FromStr for #name #ty_generics #where_clause {
+            type Err = ::aoc::parse::FromRegexError;
+
+            fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+                let mut invalid: ::std::option::Option<::aoc::parse::FromRegexError> = None;
+                #(#attempts)*
+                Err(invalid.unwrap_or_else(|| ::aoc::parse::FromRegexError::NoMatch(s.to_owned())))
+            }
+        }
+    })
+}
@@ -1,3 +1,6 @@
+mod from_regex;
+mod static_regex;
+
 use proc_macro::TokenStream;
 use proc_macro2::TokenTree;
 use quote::quote;
@@ -6,7 +9,7 @@ use syn::{
     parse::Parse,
     parse_macro_input,
     token::{self},
-    Ident, Type,
+    DeriveInput, Ident, Type,
 };
 
 struct InputParams {
@@ -115,3 +118,31 @@ pub fn make_day(attr: TokenStream, mut item: TokenStream) -> TokenStream {
     item.extend(TokenStream::from(res));
     item
 }
+
+/// Implements `FromStr` by matching the whole string against the `#[regex("...")]` pattern.
+///
+/// Fields are parsed with `FromStr` from the capture group of the same name, or from the groups
+/// in order if the pattern has no named group. `Option` fields are `None` when their group doesn't match.
+/// Each enum variant has its own pattern, tried in order: a variant whose pattern matches but whose
+/// fields don't parse is skipped, and its error is only returned if no later variant succeeds.
+#[proc_macro_derive(FromRegex, attributes(regex))]
+pub fn derive_from_regex(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    from_regex::derive(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Lazily compiled regexes, checked at compile time.
+///
+/// `let name = regex("...");` binds a `&'static Regex` in the current block and
+/// `static NAME = regex("...");` declares an item usable from several functions.
+/// `regex_set(["...", ...])` builds a `RegexSet` instead, and flags among `i`, `m`, `s`, `x`, `U`
+/// and `R` can follow the patterns: `regex("...", i, x)`.
+#[proc_macro]
+pub fn static_regex(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as static_regex::StaticRegex);
+    static_regex::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
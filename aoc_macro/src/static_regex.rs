@@ -0,0 +1,176 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use regex::RegexBuilder;
+use syn::{
+    bracketed, parenthesized,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    Error, Ident, LitStr, Token, Visibility,
+};
+
+enum Binding {
+    /// `let name = ...;`, a `&'static` reference local to the block.
+    Let(Ident),
+    /// `static NAME = ...;`, an item usable from anywhere in the module.
+    Static(Visibility, Ident),
+}
+
+enum Patterns {
+    Single(LitStr),
+    Set(Vec<LitStr>),
+}
+
+struct Entry {
+    binding: Binding,
+    patterns: Patterns,
+    flags: Vec<Flag>,
+}
+
+type Enable = fn(&mut RegexBuilder, bool) -> &mut RegexBuilder;
+
+/// Each flag, named after the inline regex flags, with the builder method it enables.
+const FLAGS: [(&str, &str, Enable); 6] = [
+    ("i", "case_insensitive", RegexBuilder::case_insensitive),
+    ("m", "multi_line", RegexBuilder::multi_line),
+    (
+        "s",
+        "dot_matches_new_line",
+        RegexBuilder::dot_matches_new_line,
+    ),
+    ("x", "ignore_whitespace", RegexBuilder::ignore_whitespace),
+    ("U", "swap_greed", RegexBuilder::swap_greed),
+    ("R", "crlf", RegexBuilder::crlf),
+];
+
+struct Flag {
+    /// Method enabling the flag in the generated code.
+    method: Ident,
+    /// Same method on the builder used for validation.
+    enable: Enable,
+}
+
+impl Parse for Flag {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let flag: Ident = input.parse()?;
+        FLAGS
+            .iter()
+            .find(|(name, _, _)| flag == name)
+            .map(|&(_, method, enable)| Flag {
+                method: Ident::new(method, flag.span()),
+                enable,
+            })
+            .ok_or_else(|| {
+                Error::new(
+                    flag.span(),
+                    "unknown flag, expected one of i, m, s, x, U, R",
+                )
+            })
+    }
+}
+
+impl Parse for Entry {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let binding = if input.peek(Token![let]) {
+            input.parse::<Token![let]>()?;
+            Binding::Let(input.parse()?)
+        } else {
+            let vis = input.parse()?;
+            input.parse::<Token![static]>()?;
+            Binding::Static(vis, input.parse()?)
+        };
+        input.parse::<Token![=]>()?;
+
+        let kind: Ident = input.parse()?;
+        let args;
+        parenthesized!(args in input);
+        let patterns = if kind == "regex" {
+            Patterns::Single(args.parse()?)
+        } else if kind == "regex_set" {
+            let list;
+            bracketed!(list in args);
+            let patterns = Punctuated::<LitStr, Token![,]>::parse_terminated(&list)?;
+            Patterns::Set(patterns.into_iter().collect())
+        } else {
+            return Err(Error::new(kind.span(), "expected `regex` or `regex_set`"));
+        };
+        let mut flags = Vec::new();
+        while !args.is_empty() {
+            args.parse::<Token![,]>()?;
+            if args.is_empty() {
+                break;
+            }
+            flags.push(args.parse()?);
+        }
+        input.parse::<Token![;]>()?;
+
+        Ok(Entry {
+            binding,
+            patterns,
+            flags,
+        })
+    }
+}
+
+pub struct StaticRegex {
+    entries: Vec<Entry>,
+}
+
+impl Parse for StaticRegex {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut entries = Vec::new();
+        while !input.is_empty() {
+            entries.push(input.parse()?);
+        }
+        Ok(StaticRegex { entries })
+    }
+}
+
+/// Builds the patterns now so errors show up at compile time, each one on its own for sets.
+fn validate(entry: &Entry) -> syn::Result<()> {
+    let patterns = match &entry.patterns {
+        Patterns::Single(pattern) => std::slice::from_ref(pattern),
+        Patterns::Set(patterns) => patterns.as_slice(),
+    };
+    for pattern in patterns {
+        let mut builder = RegexBuilder::new(&pattern.value());
+        for flag in &entry.flags {
+            (flag.enable)(&mut builder, true);
+        }
+        builder
+            .build()
+            .map_err(|e| Error::new(pattern.span(), e.to_string()))?;
+    }
+    Ok(())
+}
+
+fn expand_entry(entry: &Entry) -> syn::Result<TokenStream> {
+    validate(entry)?;
+    let methods = entry.flags.iter().map(|flag| &flag.method);
+    let (ty, build) = match &entry.patterns {
+        Patterns::Single(pattern) => (
+            quote! { ::aoc::regex::Regex },
+            quote! { ::aoc::regex::RegexBuilder::new(#pattern) },
+        ),
+        Patterns::Set(patterns) => (
+            quote! { ::aoc::regex::RegexSet },
+            quote! { ::aoc::regex::RegexSetBuilder::new([#(#patterns),*]) },
+        ),
+    };
+    let init = quote! { #build #(.#methods(true))* .build().unwrap() };
+
+    Ok(match &entry.binding {
+        Binding::Let(name) => quote! {
+            let #name = {
+                static PATTERN: ::std::sync::OnceLock<#ty> = ::std::sync::OnceLock::new();
+                PATTERN.get_or_init(|| #init)
+            };
+        },
+        Binding::Static(vis, name) => quote! {
+            #vis static #name: ::std::sync::LazyLock<#ty> = ::std::sync::LazyLock::new(|| #init);
+        },
+    })
+}
+
+pub fn expand(input: StaticRegex) -> syn::Result<TokenStream> {
+    input.entries.iter().map(expand_entry).collect()
+}